@@ -1,27 +1,95 @@
 use {
-    crate::syntax_tree::asp::{Head, Predicate, Program},
+    crate::syntax_tree::asp::{AtomicFormula, Head, Literal, Predicate, Program, Sign},
     indexmap::IndexSet,
-    petgraph::{algo::is_cyclic_directed, graph::DiGraph},
+    petgraph::{
+        algo::{is_cyclic_directed, tarjan_scc},
+        graph::{DiGraph, NodeIndex},
+    },
     std::collections::HashMap,
 };
 
 pub trait PrivateRecursion {
     fn has_private_recursion(&self, private_predicates: &IndexSet<Predicate>) -> bool;
+
+    /// Whether the private predicates may not be uniquely determined by the public ones,
+    /// i.e. whether a private predicate occurs in the head of a choice rule or depends negatively on itself through private predicates
+    fn has_nonpositive_private_recursion(&self, private_predicates: &IndexSet<Predicate>) -> bool;
 }
 
 impl PrivateRecursion for Program {
     fn has_private_recursion(&self, private_predicates: &IndexSet<Predicate>) -> bool {
+        if self.has_private_choice(private_predicates) {
+            return true;
+        }
+
+        let (dependency_graph, _) = self.private_dependency_graph(private_predicates);
+        is_cyclic_directed(&dependency_graph)
+    }
+
+    fn has_nonpositive_private_recursion(&self, private_predicates: &IndexSet<Predicate>) -> bool {
+        if self.has_private_choice(private_predicates) {
+            return true;
+        }
+
+        let (dependency_graph, mapping) = self.private_dependency_graph(private_predicates);
+        let mut component = HashMap::new();
+        for (i, nodes) in tarjan_scc(&dependency_graph).into_iter().enumerate() {
+            for node in nodes {
+                component.insert(node, i);
+            }
+        }
+
         for rule in &self.rules {
-            match rule.head {
-                Head::Choice(ref a) => {
-                    if private_predicates.contains(&a.predicate()) {
+            let Some(head_predicate) = rule.head.predicate() else {
+                continue;
+            };
+            if !private_predicates.contains(&head_predicate) {
+                continue;
+            }
+
+            for formula in &rule.body.formulas {
+                if let AtomicFormula::Literal(Literal {
+                    sign: Sign::Negation | Sign::DoubleNegation,
+                    atom,
+                }) = formula
+                {
+                    let body_predicate = atom.predicate();
+                    if private_predicates.contains(&body_predicate)
+                        && component[&mapping[&head_predicate]]
+                            == component[&mapping[&body_predicate]]
+                    {
                         return true;
                     }
                 }
-                Head::Basic(_) | Head::Falsity => (),
             }
         }
 
+        false
+    }
+}
+
+trait PrivateDependencies {
+    fn has_private_choice(&self, private_predicates: &IndexSet<Predicate>) -> bool;
+
+    /// The graph with an edge from every private head predicate to each private predicate occurring in the body of its rules
+    fn private_dependency_graph(
+        &self,
+        private_predicates: &IndexSet<Predicate>,
+    ) -> (DiGraph<(), ()>, HashMap<Predicate, NodeIndex>);
+}
+
+impl PrivateDependencies for Program {
+    fn has_private_choice(&self, private_predicates: &IndexSet<Predicate>) -> bool {
+        self.rules.iter().any(|rule| match rule.head {
+            Head::Choice(ref a) => private_predicates.contains(&a.predicate()),
+            Head::Basic(_) | Head::Falsity => false,
+        })
+    }
+
+    fn private_dependency_graph(
+        &self,
+        private_predicates: &IndexSet<Predicate>,
+    ) -> (DiGraph<(), ()>, HashMap<Predicate, NodeIndex>) {
         let mut dependency_graph = DiGraph::<(), ()>::new();
         let mut mapping = HashMap::new();
 
@@ -48,7 +116,7 @@ impl PrivateRecursion for Program {
             }
         }
 
-        is_cyclic_directed(&dependency_graph)
+        (dependency_graph, mapping)
     }
 }
 
@@ -89,4 +157,36 @@ mod tests {
                 .has_private_recursion(&private_predicates))
         }
     }
+
+    #[test]
+    fn test_nonpositive_private_recursion() {
+        let private_predicates: IndexSet<Predicate> = IndexSet::from_iter(
+            ["a/0", "b/0", "p/1"]
+                .into_iter()
+                .map(|p| p.parse().unwrap()),
+        );
+
+        for program in [
+            "a :- not c. c :- not a.",
+            "a :- a.",
+            "a :- b. b :- a, not c.",
+            "p(X) :- q(X). p(Y) :- p(X), e(X, Y). a :- not p(1).",
+        ] {
+            assert!(!Program::from_str(program)
+                .unwrap()
+                .has_nonpositive_private_recursion(&private_predicates))
+        }
+
+        for program in [
+            "{a}.",
+            "a :- not a.",
+            "a :- not not a.",
+            "a :- not b. b :- not a.",
+            "a :- b. b :- not a.",
+        ] {
+            assert!(Program::from_str(program)
+                .unwrap()
+                .has_nonpositive_private_recursion(&private_predicates))
+        }
+    }
 }
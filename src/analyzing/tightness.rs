@@ -1,20 +1,34 @@
 use {
-    crate::syntax_tree::asp::Program,
-    petgraph::{algo::is_cyclic_directed, graph::DiGraph},
+    crate::syntax_tree::asp::{Predicate, Program},
+    indexmap::IndexSet,
+    petgraph::{
+        algo::{is_cyclic_directed, tarjan_scc},
+        graph::DiGraph,
+    },
     std::collections::HashMap,
 };
 
 pub trait Tightness {
     fn is_tight(&self) -> bool;
+
+    /// The graph with an edge from the head predicate of every rule to each predicate occurring positively in its body
+    fn positive_dependency_graph(&self) -> DiGraph<Predicate, ()>;
+
+    /// The sets of predicates that positively depend on each other, i.e. the cyclic strongly connected components of the positive dependency graph
+    fn recursive_components(&self) -> Vec<IndexSet<Predicate>>;
 }
 
 impl Tightness for Program {
     fn is_tight(&self) -> bool {
-        let mut dependency_graph = DiGraph::<(), ()>::new();
+        !is_cyclic_directed(&self.positive_dependency_graph())
+    }
+
+    fn positive_dependency_graph(&self) -> DiGraph<Predicate, ()> {
+        let mut dependency_graph = DiGraph::<Predicate, ()>::new();
         let mut mapping = HashMap::new();
 
         for predicate in self.predicates() {
-            let node = dependency_graph.add_node(());
+            let node = dependency_graph.add_node(predicate.clone());
             mapping.insert(predicate, node);
         }
 
@@ -30,13 +44,45 @@ impl Tightness for Program {
             }
         }
 
-        !is_cyclic_directed(&dependency_graph)
+        dependency_graph
+    }
+
+    fn recursive_components(&self) -> Vec<IndexSet<Predicate>> {
+        let dependency_graph = self.positive_dependency_graph();
+
+        // Order the components and their predicates by the occurrence of the predicates in the program
+        let mut components: Vec<_> = tarjan_scc(&dependency_graph)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || dependency_graph.contains_edge(component[0], component[0])
+            })
+            .map(|mut component| {
+                component.sort();
+                component
+            })
+            .collect();
+        components.sort();
+
+        components
+            .into_iter()
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|node| dependency_graph[node].clone())
+                    .collect()
+            })
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use {super::Tightness, crate::syntax_tree::asp::Program, std::str::FromStr};
+    use {
+        super::Tightness,
+        crate::syntax_tree::asp::{Predicate, Program},
+        indexmap::IndexSet,
+        std::str::FromStr,
+    };
 
     #[test]
     fn test_tightness() {
@@ -58,4 +104,28 @@ mod tests {
             assert!(!Program::from_str(program).unwrap().is_tight())
         }
     }
+
+    #[test]
+    fn test_recursive_components() {
+        for (program, target) in [
+            ("a :- not a. p(X) :- q(X).", vec![]),
+            ("a :- a. b :- a.", vec![vec!["a/0"]]),
+            (
+                "p :- q, not r. p :- r. r :- p. t(X) :- e(X, Y), t(Y).",
+                vec![vec!["p/0", "r/0"], vec!["t/1"]],
+            ),
+        ] {
+            let components = Program::from_str(program).unwrap().recursive_components();
+            let target: Vec<IndexSet<Predicate>> = target
+                .into_iter()
+                .map(|component| {
+                    component
+                        .into_iter()
+                        .map(|predicate| predicate.parse().unwrap())
+                        .collect()
+                })
+                .collect();
+            assert_eq!(components, target, "{program}");
+        }
+    }
 }
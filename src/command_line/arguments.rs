@@ -1,6 +1,7 @@
 use {
-    clap::{Parser, Subcommand, ValueEnum},
-    std::path::PathBuf,
+    crate::verifying::prover::vampire::{MemoryLimit, VampireMode},
+    clap::{Args, Parser, Subcommand, ValueEnum},
+    std::{path::PathBuf, str::FromStr},
 };
 
 #[derive(Debug, Parser)]
@@ -41,68 +42,24 @@ pub enum Command {
         #[arg(long, value_enum)]
         with: Translation,
 
+        /// Annotate every formula with the rules or formulas and the translations it originates from
+        #[arg(long)]
+        trace: bool,
+
         /// The file to translate
         input: Option<PathBuf>,
     },
 
     /// Create and optionally verify a set of problem files from a claim about answer set programs or first-order theories
-    Verify {
-        /// The equivalence theory used to proof the claim
-        #[arg(long, value_enum)]
-        equivalence: Equivalence,
-
-        /// The decomposition strategy to use
-        #[arg(long, value_enum, default_value_t)]
-        decomposition: Decomposition,
-
-        /// The direction of the proof
-        #[arg(long, value_enum, default_value_t)]
-        direction: Direction,
-
-        /// Bypass the tightness checks during verification of external equivalence
-        #[arg(long, action)]
-        bypass_tightness: bool,
-
-        /// Omit simplifications
-        #[arg(long, action)]
-        no_simplify: bool,
-
-        /// Omit breaking equivalences
-        #[arg(long, action)]
-        no_eq_break: bool,
-
-        /// Omit proof search and just create problem files
-        #[arg(long, action)]
-        no_proof_search: bool,
-
-        /// Omit display of system runtimes
-        #[arg(long, action)]
-        no_timing: bool,
-
-        /// The time limit in seconds to prove each problem passed to a prover
-        #[arg(long, short, default_value_t = 60)]
-        time_limit: usize,
-
-        /// The number of prover instances to spawn
-        #[arg(long, short = 'n', default_value_t = 1)]
-        prover_instances: usize,
-
-        /// The number of threads each prover may use
-        #[arg(long, short = 'm', default_value_t = 1)]
-        prover_cores: usize,
-
-        /// The destination directory for the problem files
-        #[arg(long)]
-        save_problems: Option<PathBuf>,
-
-        /// A set of files from which to construct the claim, including
-        ///
-        ///   - a specification of intended behavior,
-        ///   - a program about which the claim is constructed, and
-        ///   - additional knowledge used to construct the claim (e.g., user guide, proof outline).
-        #[arg(verbatim_doc_comment)]
-        files: Vec<PathBuf>,
-    },
+    #[command(after_help = "Exit codes:
+  0  all problems were proven (or no proof search was requested)
+  1  an unexpected failure occurred, e.g. a file could not be written
+  2  the command line arguments are invalid
+  3  the conjectures of some problem do not follow from its axioms
+  4  some problem could neither be proven nor disproven, e.g. because of a timeout
+  5  the input files are malformed or do not form a valid task
+  6  a prover failed with an error")]
+    Verify(Box<VerifyArguments>),
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -127,9 +84,13 @@ pub enum SimplificationStrategy {
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 pub enum Translation {
+    Asp,
     Completion,
     Gamma,
+    Natural,
+    OrderedCompletion,
     TauStar,
+    Tptp,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -145,15 +106,214 @@ pub enum Decomposition {
     Sequential,
 }
 
+#[derive(Args, Clone, Debug)]
+pub struct VerifyArguments {
+    /// The equivalence theory used to proof the claim, required unless given by a manifest
+    #[arg(long, value_enum)]
+    pub equivalence: Option<Equivalence>,
+
+    /// The decomposition strategy to use [default: sequential]
+    #[arg(long, value_enum)]
+    pub decomposition: Option<Decomposition>,
+
+    /// The direction of the proof [default: universal]
+    #[arg(long, value_enum)]
+    pub direction: Option<Direction>,
+
+    /// Verify non-tight programs without their loop formulas, which is unsound
+    #[arg(long, action)]
+    pub bypass_tightness: bool,
+
+    /// Translate rules without arithmetic or intervals naturally instead of by tau-star
+    #[arg(long, action)]
+    pub natural: bool,
+
+    /// Verify strong equivalence with an intuitionistic prover and the axioms of here-and-there instead of gamma
+    #[arg(long, action)]
+    pub intuitionistic: bool,
+
+    /// Omit simplifications
+    #[arg(long, action)]
+    pub no_simplify: bool,
+
+    /// Omit breaking equivalences
+    #[arg(long, action)]
+    pub no_eq_break: bool,
+
+    /// Omit proof search and just create problem files
+    #[arg(long, action)]
+    pub no_proof_search: bool,
+
+    /// Omit the search for countermodels of problems whose conjectures do not follow from their axioms
+    #[arg(long, action)]
+    pub no_countermodel_search: bool,
+
+    /// Print the proofs found by the prover in anthem's syntax
+    #[arg(long, action)]
+    pub show_proofs: bool,
+
+    /// Omit display of system runtimes
+    #[arg(long, action)]
+    pub no_timing: bool,
+
+    /// The time limit in seconds to prove each problem passed to a prover [default: 60]
+    #[arg(long, short)]
+    pub time_limit: Option<usize>,
+
+    /// The time in seconds a prover may exceed its time limit before it is killed [default: 5]
+    #[arg(long)]
+    pub grace_period: Option<usize>,
+
+    /// The number of prover instances to spawn [default: 1]
+    #[arg(long, short = 'n')]
+    pub prover_instances: Option<usize>,
+
+    /// The number of threads each prover may use [default: 1]
+    #[arg(long, short = 'm')]
+    pub prover_cores: Option<usize>,
+
+    /// The memory limit in MiB of vampire for all problems, or for the problems of the given name, e.g. `4096` or `backward=8192`
+    #[arg(long, value_name = "[PROBLEM=]MIB")]
+    pub memory_limit: Vec<MemoryLimit>,
+
+    /// The vampire binary to use as a prover
+    #[arg(long, env = "ANTHEM_VAMPIRE", default_value = "vampire")]
+    pub vampire_binary: PathBuf,
+
+    /// The intuitionistic prover to use with `--intuitionistic`, which reads a problem from stdin and prints its SZS status
+    #[arg(long, env = "ANTHEM_INTUITIONISTIC_PROVER", value_name = "BINARY")]
+    pub intuitionistic_prover: Option<PathBuf>,
+
+    /// The vampire mode to use, several modes race against each other in a portfolio [default: casc]
+    #[arg(long, value_enum)]
+    pub vampire_mode: Vec<VampireMode>,
+
+    /// An additional argument passed verbatim to vampire
+    #[arg(long, allow_hyphen_values = true)]
+    pub vampire_argument: Vec<String>,
+
+    /// Skip problems that rely on conjectures of earlier problems which were not proven
+    #[arg(long, action)]
+    pub fail_fast: bool,
+
+    /// Prove every problem, even if the cache holds a proof or disproof of it
+    #[arg(long, action)]
+    pub no_cache: bool,
+
+    /// The directory in which proofs and disproofs are cached [default: $XDG_CACHE_HOME/anthem or ~/.cache/anthem]
+    #[arg(long, env = "ANTHEM_CACHE_DIR", value_name = "DIR")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Write a report of the verification in the given format to the given path, e.g. `json=report.json` or `junit=report.xml`
+    #[arg(long, value_name = "FORMAT=PATH", conflicts_with = "no_proof_search")]
+    pub report: Vec<ReportTarget>,
+
+    /// Verify every example directory below the given directory and summarize the results
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with_all = ["files", "left", "right", "spec", "program", "user_guide", "outline"]
+    )]
+    pub batch: Option<PathBuf>,
+
+    /// Verify all examples of a batch, including those unchanged since their last success
+    #[arg(long, action, requires = "batch")]
+    pub no_resume: bool,
+
+    /// The destination directory for the problem files
+    #[arg(long)]
+    pub save_problems: Option<PathBuf>,
+
+    /// The left program of a claim of strong equivalence
+    #[arg(long)]
+    pub left: Option<PathBuf>,
+
+    /// The right program of a claim of strong equivalence
+    #[arg(long)]
+    pub right: Option<PathBuf>,
+
+    /// The specification of a claim of external equivalence, either a program or a specification
+    #[arg(long)]
+    pub spec: Option<PathBuf>,
+
+    /// The program of a claim of external equivalence
+    #[arg(long)]
+    pub program: Option<PathBuf>,
+
+    /// The user guide of a claim of external equivalence
+    #[arg(long)]
+    pub user_guide: Vec<PathBuf>,
+
+    /// The proof outline of a claim of external equivalence
+    #[arg(long)]
+    pub outline: Vec<PathBuf>,
+
+    /// A set of files from which to construct the claim, including
+    ///
+    ///   - a specification of intended behavior,
+    ///   - a program about which the claim is constructed, and
+    ///   - additional knowledge used to construct the claim (e.g., user guide, proof outline).
+    ///
+    /// The roles of these files are inferred from their extensions, unless given explicitly.
+    /// A directory containing an `anthem.toml` manifest contributes the files and settings named in it.
+    #[arg(verbatim_doc_comment)]
+    pub files: Vec<PathBuf>,
+}
+
 pub use crate::syntax_tree::fol::Direction;
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum ReportFormat {
+    Json,
+    Junit,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReportTarget {
+    pub format: ReportFormat,
+    pub path: PathBuf,
+}
+
+impl FromStr for ReportTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (format, path) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected `<format>=<path>`, found `{s}`"))?;
+
+        if path.is_empty() {
+            return Err(format!("no path was provided for the `{format}` report"));
+        }
+
+        Ok(ReportTarget {
+            format: ReportFormat::from_str(format, true)?,
+            path: path.into(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Arguments;
+    use super::{Arguments, ReportFormat, ReportTarget};
 
     #[test]
     fn verify() {
         use clap::CommandFactory as _;
         Arguments::command().debug_assert()
     }
+
+    #[test]
+    fn report_target() {
+        assert_eq!(
+            "json=out/report.json".parse(),
+            Ok(ReportTarget {
+                format: ReportFormat::Json,
+                path: "out/report.json".into(),
+            })
+        );
+        assert!("json".parse::<ReportTarget>().is_err());
+        assert!("json=".parse::<ReportTarget>().is_err());
+        assert!("yaml=report.yaml".parse::<ReportTarget>().is_err());
+    }
 }
@@ -0,0 +1,324 @@
+use {
+    crate::{
+        command_line::manifest::MANIFEST,
+        verifying::{prover::Verdict, summary::Summary},
+    },
+    anyhow::{Context, Result},
+    serde::{Deserialize, Serialize},
+    sha2::{Digest, Sha256},
+    std::{
+        collections::BTreeMap,
+        ffi::OsStr,
+        fmt::{self, Display},
+        fs,
+        path::{Path, PathBuf},
+    },
+    walkdir::WalkDir,
+};
+
+/// The name of the file in which a batch records the examples that were verified successfully
+pub const BATCH_STATE: &str = ".anthem-batch.json";
+
+/// A directory containing the files of a single verification
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Example {
+    /// The path of the directory relative to the directory of the batch
+    pub name: String,
+    pub directory: PathBuf,
+    pub files: Vec<PathBuf>,
+}
+
+impl Example {
+    /// A hash of the names and contents of all files of the example and of the given settings
+    pub fn fingerprint(&self, settings: &str) -> Result<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(settings.as_bytes());
+        for path in &self.files {
+            let content = fs::read(path)
+                .with_context(|| format!("could not read file `{}`", path.display()))?;
+            hasher.update(path.file_name().unwrap_or_default().as_encoded_bytes());
+            hasher.update((content.len() as u64).to_le_bytes());
+            hasher.update(&content);
+        }
+        Ok(hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect())
+    }
+}
+
+/// Find all directories below the given one that directly contain a program, specification, user guide, proof outline or manifest
+pub fn discover(directory: &Path) -> Result<Vec<Example>> {
+    let mut examples = vec![];
+
+    let entries = WalkDir::new(directory)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.depth() == 0 || !is_hidden(entry.path()));
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+
+        let mut files = vec![];
+        for file in fs::read_dir(entry.path())
+            .with_context(|| format!("could not read directory `{}`", entry.path().display()))?
+        {
+            let path = file?.path();
+            if path.is_file() && !is_hidden(&path) {
+                files.push(path);
+            }
+        }
+        files.sort();
+
+        if files.iter().any(|path| is_verification_file(path)) {
+            let name = match entry.path().strip_prefix(directory) {
+                Ok(name) if name.as_os_str().is_empty() => ".".to_string(),
+                Ok(name) => name.display().to_string(),
+                Err(_) => entry.path().display().to_string(),
+            };
+            examples.push(Example {
+                name,
+                directory: entry.into_path(),
+                files,
+            });
+        }
+    }
+
+    Ok(examples)
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(OsStr::to_str)
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+fn is_verification_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == MANIFEST)
+        || matches!(
+            path.extension().and_then(OsStr::to_str),
+            Some("lp" | "spec" | "ug" | "po")
+        )
+}
+
+/// The last successful verification of an example
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Success {
+    pub fingerprint: String,
+    pub problems: usize,
+}
+
+/// The examples of a batch that were verified successfully, such that unchanged ones can be skipped
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchState {
+    pub successes: BTreeMap<String, Success>,
+}
+
+impl BatchState {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(BatchState::default());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("could not read file `{}`", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("could not parse file `{}`", path.display()))
+    }
+
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content)
+            .with_context(|| format!("could not write file `{}`", path.display()))
+    }
+
+    /// The last success of an example, provided that it still has the given fingerprint
+    pub fn success(&self, example: &Example, fingerprint: &str) -> Option<&Success> {
+        self.successes
+            .get(&example.name)
+            .filter(|success| success.fingerprint == fingerprint)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExampleOutcome {
+    /// The example was verified with the given summary
+    Verified(Summary),
+    /// The example was skipped, as it is unchanged since its last success
+    Unchanged(Success),
+    /// The verification of the example failed with an error
+    Failed(String),
+}
+
+/// A row of the table summarizing a batch
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Row {
+    pub example: String,
+    pub outcome: ExampleOutcome,
+}
+
+/// A table listing the verdict, number of problems, proved and failed problems and time per example
+pub struct Table<'a>(pub &'a [Row]);
+
+impl Display for Table<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut cells = vec![[
+            "example".to_string(),
+            "verdict".to_string(),
+            "problems".to_string(),
+            "proved".to_string(),
+            "failed".to_string(),
+            "time".to_string(),
+        ]];
+
+        for row in self.0 {
+            let [verdict, problems, proved, failed, time] = match &row.outcome {
+                ExampleOutcome::Verified(summary) => {
+                    let proved = summary
+                        .problems
+                        .iter()
+                        .filter(|problem| problem.verdict == Verdict::Proved)
+                        .count();
+                    [
+                        summary.verdict.to_string(),
+                        summary.problems.len().to_string(),
+                        proved.to_string(),
+                        (summary.problems.len() - proved).to_string(),
+                        match summary.elapsed_ms {
+                            Some(elapsed_ms) => format!("{elapsed_ms} ms"),
+                            None => "-".to_string(),
+                        },
+                    ]
+                }
+                ExampleOutcome::Unchanged(success) => [
+                    "proved (unchanged)".to_string(),
+                    success.problems.to_string(),
+                    success.problems.to_string(),
+                    "0".to_string(),
+                    "-".to_string(),
+                ],
+                ExampleOutcome::Failed(error) => [
+                    error.clone(),
+                    "-".to_string(),
+                    "-".to_string(),
+                    "-".to_string(),
+                    "-".to_string(),
+                ],
+            };
+            cells.push([row.example.clone(), verdict, problems, proved, failed, time]);
+        }
+
+        let mut widths = [0; 6];
+        for row in &cells {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        for row in &cells {
+            let line: Vec<_> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect();
+            writeln!(f, "{}", line.join("  ").trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{discover, BatchState, Example, ExampleOutcome, Row, Success, Table},
+        crate::verifying::{prover::Verdict, summary::Summary},
+        std::path::Path,
+    };
+
+    #[test]
+    fn test_discover() {
+        let directory = Path::new("res/examples/strong_equivalence");
+        let examples = discover(directory).unwrap();
+
+        let names: Vec<_> = examples
+            .iter()
+            .map(|example| example.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            ["bounds", "choice", "successor", "transitive", "trivial"]
+        );
+        assert_eq!(
+            examples[0].files,
+            [
+                directory.join("bounds/README.md"),
+                directory.join("bounds/bounds.1.lp"),
+                directory.join("bounds/bounds.2.lp"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let example = Example {
+            name: "choice".into(),
+            directory: "res/examples/strong_equivalence/choice".into(),
+            files: vec!["res/examples/strong_equivalence/choice/choice.1.lp".into()],
+        };
+
+        let fingerprint = example.fingerprint("strong").unwrap();
+        assert_eq!(fingerprint.len(), 64);
+        assert_eq!(fingerprint, example.fingerprint("strong").unwrap());
+        assert_ne!(fingerprint, example.fingerprint("external").unwrap());
+
+        let mut state = BatchState::default();
+        state.successes.insert(
+            "choice".into(),
+            Success {
+                fingerprint: fingerprint.clone(),
+                problems: 2,
+            },
+        );
+        assert!(state.success(&example, &fingerprint).is_some());
+        assert!(state.success(&example, "changed").is_none());
+    }
+
+    #[test]
+    fn test_table() {
+        let mut summary = Summary::new(vec![]);
+        summary.verdict = Verdict::Unknown;
+        summary.elapsed_ms = Some(1500);
+
+        let rows = [
+            Row {
+                example: "coloring".into(),
+                outcome: ExampleOutcome::Verified(summary),
+            },
+            Row {
+                example: "primes/simple".into(),
+                outcome: ExampleOutcome::Unchanged(Success {
+                    fingerprint: "".into(),
+                    problems: 4,
+                }),
+            },
+            Row {
+                example: "orphan".into(),
+                outcome: ExampleOutcome::Failed("invalid input".into()),
+            },
+        ];
+
+        assert_eq!(
+            Table(&rows).to_string(),
+            "example        verdict             problems  proved  failed  time
+coloring       unknown             0         0       0       1500 ms
+primes/simple  proved (unchanged)  4         4       0       -
+orphan         invalid input       -         -       -       -
+"
+        );
+    }
+}
@@ -1,9 +1,74 @@
 use {
+    crate::{
+        convenience::with_warnings::WithWarnings,
+        syntax_tree::{
+            fol::{Predicate, Sort, Specification, UserGuide, UserGuideEntry},
+            Node as _,
+        },
+    },
     either::Either,
-    std::{ffi::OsStr, path::PathBuf},
+    indexmap::{map::Entry, IndexMap, IndexSet},
+    itertools::Itertools as _,
+    std::{
+        ffi::OsStr,
+        fmt::{self, Display},
+        fs,
+        path::{Path, PathBuf},
+    },
+    thiserror::Error,
     walkdir::WalkDir,
 };
 
+/// Files whose roles were given explicitly rather than inferred from their extensions
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Roles {
+    pub left: Option<PathBuf>,
+    pub right: Option<PathBuf>,
+    pub specification: Option<PathBuf>,
+    pub program: Option<PathBuf>,
+    pub user_guides: Vec<PathBuf>,
+    pub proof_outlines: Vec<PathBuf>,
+}
+
+impl Roles {
+    /// Combine two sets of roles, preferring the roles of `self` over those of `other`
+    pub fn or(self, other: Roles) -> Roles {
+        Roles {
+            left: self.left.or(other.left),
+            right: self.right.or(other.right),
+            specification: self.specification.or(other.specification),
+            program: self.program.or(other.program),
+            user_guides: if self.user_guides.is_empty() {
+                other.user_guides
+            } else {
+                self.user_guides
+            },
+            proof_outlines: if self.proof_outlines.is_empty() {
+                other.proof_outlines
+            } else {
+                self.proof_outlines
+            },
+        }
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        [&self.left, &self.right, &self.specification, &self.program]
+            .into_iter()
+            .flatten()
+            .chain(&self.user_guides)
+            .chain(&self.proof_outlines)
+            .any(|other| is_same_file(path, other))
+    }
+}
+
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    a == b
+        || matches!(
+            (fs::canonicalize(a), fs::canonicalize(b)),
+            (Ok(a), Ok(b)) if a == b
+        )
+}
+
 #[derive(Debug)]
 pub struct Files {
     pub specifications: Vec<PathBuf>,
@@ -11,6 +76,8 @@ pub struct Files {
     pub user_guides: Vec<PathBuf>,
     pub proof_outlines: Vec<PathBuf>,
     pub other: Vec<PathBuf>,
+    /// The explicitly given roles, which take precedence over the roles inferred from extensions
+    pub roles: Roles,
 }
 
 impl Files {
@@ -21,6 +88,7 @@ impl Files {
             user_guides: vec![],
             proof_outlines: vec![],
             other: vec![],
+            roles: Roles::default(),
         }
     }
 
@@ -49,34 +117,358 @@ impl Files {
         Ok(result)
     }
 
+    pub fn assign(mut self, roles: Roles) -> Self {
+        self.roles = roles;
+        self
+    }
+
+    /// The programs that may fill roles which were not given explicitly
+    fn unassigned_programs(&self) -> Vec<&PathBuf> {
+        self.programs
+            .iter()
+            .filter(|program| !self.roles.contains(program))
+            .collect()
+    }
+
     pub fn left(&self) -> Option<&PathBuf> {
-        self.programs.first()
+        self.roles
+            .left
+            .as_ref()
+            .or_else(|| self.unassigned_programs().first().copied())
     }
 
     pub fn right(&self) -> Option<&PathBuf> {
-        self.programs.get(1)
+        self.roles.right.as_ref().or_else(|| {
+            let skip = usize::from(self.roles.left.is_none());
+            self.unassigned_programs().get(skip).copied()
+        })
     }
 
     pub fn specification(&self) -> Option<Either<&PathBuf, &PathBuf>> {
-        self.specifications
-            .first()
-            .map(Either::Right)
-            .or_else(|| self.programs.first().map(Either::Left))
+        match &self.roles.specification {
+            Some(path) if path.extension().and_then(OsStr::to_str) == Some("lp") => {
+                Some(Either::Left(path))
+            }
+            Some(path) => Some(Either::Right(path)),
+            None => self.specifications.first().map(Either::Right).or_else(|| {
+                self.unassigned_programs()
+                    .first()
+                    .copied()
+                    .map(Either::Left)
+            }),
+        }
     }
 
     pub fn program(&self) -> Option<&PathBuf> {
-        if self.specifications.is_empty() {
-            self.programs.get(1)
+        self.roles.program.as_ref().or_else(|| {
+            let skip =
+                usize::from(self.roles.specification.is_none() && self.specifications.is_empty());
+            self.unassigned_programs().get(skip).copied()
+        })
+    }
+
+    pub fn user_guides(&self) -> &[PathBuf] {
+        if self.roles.user_guides.is_empty() {
+            &self.user_guides
         } else {
-            self.programs.first()
+            &self.roles.user_guides
         }
     }
 
-    pub fn user_guide(&self) -> Option<&PathBuf> {
-        self.user_guides.first()
+    pub fn proof_outlines(&self) -> &[PathBuf] {
+        if self.roles.proof_outlines.is_empty() {
+            &self.proof_outlines
+        } else {
+            &self.roles.proof_outlines
+        }
+    }
+
+    /// The programs, specifications, user guides and proof outlines that are not among the used files
+    pub fn ignored<'a>(&'a self, used: &[&PathBuf]) -> Vec<&'a PathBuf> {
+        self.programs
+            .iter()
+            .chain(&self.specifications)
+            .chain(&self.user_guides)
+            .chain(&self.proof_outlines)
+            .filter(|path| !used.iter().any(|used| is_same_file(path, used)))
+            .collect()
     }
+}
+
+#[derive(Debug, Error)]
+pub enum FilesWarning {
+    IgnoredFile(PathBuf),
+    MergedUserGuides(Vec<PathBuf>),
+    ConcatenatedProofOutlines(Vec<PathBuf>),
+}
+
+impl Display for FilesWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilesWarning::IgnoredFile(path) => {
+                write!(f, "the file `{}` is ignored", path.display())
+            }
+            FilesWarning::MergedUserGuides(paths) => write!(
+                f,
+                "the following user guides are merged: {}",
+                paths.iter().map(|path| path.display()).format(", ")
+            ),
+            FilesWarning::ConcatenatedProofOutlines(paths) => write!(
+                f,
+                "the following proof outlines are concatenated in this order: {}",
+                paths.iter().map(|path| path.display()).format(", ")
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UserGuideConflict {
+    #[error("the predicate `{predicate}` is declared as {first_kind} predicate in `{}` but as {second_kind} predicate in `{}`", .first.display(), .second.display())]
+    PredicateDeclaration {
+        predicate: Predicate,
+        first_kind: &'static str,
+        first: PathBuf,
+        second_kind: &'static str,
+        second: PathBuf,
+    },
+    #[error("the placeholder `{name}` is declared with sort `{first_sort}` in `{}` but with sort `{second_sort}` in `{}`", .first.display(), .second.display())]
+    PlaceholderDeclaration {
+        name: String,
+        first_sort: Sort,
+        first: PathBuf,
+        second_sort: Sort,
+        second: PathBuf,
+    },
+    #[error("the assumption `{name}` differs between `{}` and `{}`", .first.display(), .second.display())]
+    Assumption {
+        name: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
+}
+
+/// Merge user guides into one, dropping duplicate entries and rejecting conflicting declarations
+pub fn merge_user_guides(
+    user_guides: impl IntoIterator<Item = (PathBuf, UserGuide)>,
+) -> Result<UserGuide, UserGuideConflict> {
+    let mut predicates: IndexMap<Predicate, (&'static str, PathBuf)> = IndexMap::new();
+    let mut placeholders: IndexMap<String, (Sort, PathBuf)> = IndexMap::new();
+    let mut assumptions: IndexMap<String, (UserGuideEntry, PathBuf)> = IndexMap::new();
+    let mut entries = IndexSet::new();
+
+    for (path, user_guide) in user_guides {
+        for entry in user_guide {
+            match &entry {
+                UserGuideEntry::InputPredicate(predicate)
+                | UserGuideEntry::OutputPredicate(predicate) => {
+                    let kind = match entry {
+                        UserGuideEntry::InputPredicate(_) => "input",
+                        _ => "output",
+                    };
+                    match predicates.entry(predicate.clone()) {
+                        Entry::Occupied(occupied) if occupied.get().0 != kind => {
+                            let (first_kind, first) = occupied.get().clone();
+                            return Err(UserGuideConflict::PredicateDeclaration {
+                                predicate: predicate.clone(),
+                                first_kind,
+                                first,
+                                second_kind: kind,
+                                second: path,
+                            });
+                        }
+                        Entry::Occupied(_) => (),
+                        Entry::Vacant(vacant) => {
+                            vacant.insert((kind, path.clone()));
+                        }
+                    }
+                }
+                UserGuideEntry::PlaceholderDeclaration(placeholder) => {
+                    match placeholders.entry(placeholder.name.clone()) {
+                        Entry::Occupied(occupied) if occupied.get().0 != placeholder.sort => {
+                            let (first_sort, first) = occupied.get().clone();
+                            return Err(UserGuideConflict::PlaceholderDeclaration {
+                                name: placeholder.name.clone(),
+                                first_sort,
+                                first,
+                                second_sort: placeholder.sort.clone(),
+                                second: path,
+                            });
+                        }
+                        Entry::Occupied(_) => (),
+                        Entry::Vacant(vacant) => {
+                            vacant.insert((placeholder.sort.clone(), path.clone()));
+                        }
+                    }
+                }
+                UserGuideEntry::AnnotatedFormula(formula) if !formula.name.is_empty() => {
+                    match assumptions.entry(formula.name.clone()) {
+                        Entry::Occupied(occupied) if occupied.get().0 != entry => {
+                            return Err(UserGuideConflict::Assumption {
+                                name: formula.name.clone(),
+                                first: occupied.get().1.clone(),
+                                second: path,
+                            });
+                        }
+                        Entry::Occupied(_) => (),
+                        Entry::Vacant(vacant) => {
+                            vacant.insert((entry.clone(), path.clone()));
+                        }
+                    }
+                }
+                UserGuideEntry::AnnotatedFormula(_) => (),
+            }
+
+            entries.insert(entry);
+        }
+    }
+
+    Ok(entries.into_iter().collect())
+}
+
+/// Read the given user guides and merge them, warning about the merge if there is more than one
+pub fn read_user_guides(
+    paths: &[PathBuf],
+) -> anyhow::Result<WithWarnings<UserGuide, FilesWarning>> {
+    let user_guides: Vec<_> = paths
+        .iter()
+        .map(|path| Ok((path.clone(), UserGuide::from_file(path)?)))
+        .collect::<anyhow::Result<_>>()?;
+
+    let user_guide = merge_user_guides(user_guides)?;
+
+    Ok(if paths.len() > 1 {
+        WithWarnings::flawless(user_guide)
+            .add_warning(FilesWarning::MergedUserGuides(paths.to_vec()))
+    } else {
+        WithWarnings::flawless(user_guide)
+    })
+}
+
+/// Read the given proof outlines and concatenate them in the given order
+///
+/// Formulas of a proof outline that already occur in a preceding one are dropped, such that
+/// outlines may share definitions.
+pub fn read_proof_outlines(
+    paths: &[PathBuf],
+) -> anyhow::Result<WithWarnings<Specification, FilesWarning>> {
+    let mut proof_outline = Specification::empty();
+    for path in paths {
+        let preceding = proof_outline.formulas.len();
+        for formula in Specification::from_file(path)? {
+            if !proof_outline.formulas[..preceding].contains(&formula) {
+                proof_outline.formulas.push(formula);
+            }
+        }
+    }
+
+    Ok(if paths.len() > 1 {
+        WithWarnings::flawless(proof_outline)
+            .add_warning(FilesWarning::ConcatenatedProofOutlines(paths.to_vec()))
+    } else {
+        WithWarnings::flawless(proof_outline)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{merge_user_guides, Files, Roles, UserGuideConflict},
+        crate::syntax_tree::fol::UserGuide,
+        either::Either,
+        std::path::PathBuf,
+    };
+
+    fn files(programs: &[&str], specifications: &[&str], roles: Roles) -> Files {
+        Files {
+            programs: programs.iter().map(PathBuf::from).collect(),
+            specifications: specifications.iter().map(PathBuf::from).collect(),
+            ..Files::empty()
+        }
+        .assign(roles)
+    }
+
+    #[test]
+    fn test_roles() {
+        let inferred = files(&["a.lp", "b.lp"], &[], Roles::default());
+        assert_eq!(inferred.left(), Some(&"a.lp".into()));
+        assert_eq!(inferred.right(), Some(&"b.lp".into()));
+        assert_eq!(inferred.specification(), Some(Either::Left(&"a.lp".into())));
+        assert_eq!(inferred.program(), Some(&"b.lp".into()));
+
+        let swapped = files(
+            &["a.lp", "b.lp"],
+            &[],
+            Roles {
+                left: Some("b.lp".into()),
+                specification: Some("b.lp".into()),
+                ..Roles::default()
+            },
+        );
+        assert_eq!(swapped.left(), Some(&"b.lp".into()));
+        assert_eq!(swapped.right(), Some(&"a.lp".into()));
+        assert_eq!(swapped.specification(), Some(Either::Left(&"b.lp".into())));
+        assert_eq!(swapped.program(), Some(&"a.lp".into()));
+
+        let specified = files(
+            &["a.lp", "b.lp"],
+            &["x.spec"],
+            Roles {
+                program: Some("b.lp".into()),
+                ..Roles::default()
+            },
+        );
+        assert_eq!(
+            specified.specification(),
+            Some(Either::Right(&"x.spec".into()))
+        );
+        assert_eq!(specified.program(), Some(&"b.lp".into()));
+    }
+
+    #[test]
+    fn test_merge_user_guides() {
+        let user_guide = |path: &str, source: &str| -> (PathBuf, UserGuide) {
+            (path.into(), source.parse().unwrap())
+        };
+
+        let merged = merge_user_guides([
+            user_guide(
+                "library.ug",
+                "input: p/1. input: n -> integer. assumption[positive]: n > 0.",
+            ),
+            user_guide(
+                "local.ug",
+                "input: p/1. output: q/1. assumption[positive]: n > 0.",
+            ),
+        ])
+        .unwrap();
+        assert_eq!(
+            merged,
+            "input: p/1. input: n -> integer. assumption[positive]: n > 0. output: q/1."
+                .parse()
+                .unwrap()
+        );
 
-    pub fn proof_outline(&self) -> Option<&PathBuf> {
-        self.proof_outlines.first()
+        assert!(matches!(
+            merge_user_guides([
+                user_guide("library.ug", "input: p/1."),
+                user_guide("local.ug", "output: p/1."),
+            ]),
+            Err(UserGuideConflict::PredicateDeclaration { .. })
+        ));
+        assert!(matches!(
+            merge_user_guides([
+                user_guide("library.ug", "input: n -> integer."),
+                user_guide("local.ug", "input: n -> symbol."),
+            ]),
+            Err(UserGuideConflict::PlaceholderDeclaration { .. })
+        ));
+        assert!(matches!(
+            merge_user_guides([
+                user_guide("library.ug", "assumption[positive]: n > 0."),
+                user_guide("local.ug", "assumption[positive]: n > 1."),
+            ]),
+            Err(UserGuideConflict::Assumption { .. })
+        ));
     }
 }
@@ -0,0 +1,217 @@
+use {
+    crate::{
+        command_line::{
+            arguments::{Decomposition, Direction, Equivalence},
+            files::Roles,
+        },
+        verifying::prover::vampire::VampireMode,
+    },
+    anyhow::{bail, Context, Result},
+    clap::ValueEnum,
+    serde::{de, Deserialize, Deserializer},
+    std::{
+        collections::BTreeMap,
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+/// The name of the manifest file looked for in directories passed to `anthem verify`
+pub const MANIFEST: &str = "anthem.toml";
+
+/// A description of a verification, such that it can be reproduced by `anthem verify path/to/dir`
+///
+/// Paths in a manifest are relative to the directory containing the manifest.
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Manifest {
+    #[serde(deserialize_with = "value_enum")]
+    pub equivalence: Option<Equivalence>,
+    #[serde(deserialize_with = "value_enum")]
+    pub decomposition: Option<Decomposition>,
+    #[serde(deserialize_with = "value_enum")]
+    pub direction: Option<Direction>,
+    pub bypass_tightness: bool,
+    pub natural: bool,
+    pub files: ManifestFiles,
+    pub prover: ManifestProver,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ManifestFiles {
+    pub left: Option<PathBuf>,
+    pub right: Option<PathBuf>,
+    pub specification: Option<PathBuf>,
+    pub program: Option<PathBuf>,
+    pub user_guides: Vec<PathBuf>,
+    pub proof_outlines: Vec<PathBuf>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ManifestProver {
+    pub time_limit: Option<usize>,
+    pub grace_period: Option<usize>,
+    pub instances: Option<usize>,
+    pub cores: Option<usize>,
+    pub memory_limit: Option<usize>,
+    /// The memory limits of the problems of the given names, overriding `memory_limit`
+    pub memory_limits: BTreeMap<String, usize>,
+    #[serde(deserialize_with = "value_enums")]
+    pub modes: Vec<VampireMode>,
+    pub arguments: Vec<String>,
+}
+
+fn value_enum<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ValueEnum,
+{
+    let value = String::deserialize(deserializer)?;
+    T::from_str(&value, true)
+        .map(Some)
+        .map_err(de::Error::custom)
+}
+
+fn value_enums<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ValueEnum,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|value| T::from_str(value, true).map_err(de::Error::custom))
+        .collect()
+}
+
+impl Manifest {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("could not read file `{}`", path.display()))?;
+        let manifest: Manifest = toml::from_str(&content)
+            .with_context(|| format!("could not parse manifest `{}`", path.display()))?;
+
+        let directory = path.parent().unwrap_or(Path::new(""));
+        Ok(manifest.relative_to(directory))
+    }
+
+    /// Find the manifest among the given paths, which are either manifests or directories containing one
+    pub fn find(paths: &[PathBuf]) -> Result<Option<PathBuf>> {
+        let mut manifests = paths.iter().filter_map(|path| {
+            if path.is_dir() {
+                Some(path.join(MANIFEST)).filter(|manifest| manifest.is_file())
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == "toml")
+            {
+                Some(path.clone())
+            } else {
+                None
+            }
+        });
+
+        match (manifests.next(), manifests.next()) {
+            (Some(first), Some(second)) => bail!(
+                "found more than one manifest: `{}` and `{}`",
+                first.display(),
+                second.display()
+            ),
+            (manifest, _) => Ok(manifest),
+        }
+    }
+
+    fn relative_to(mut self, directory: &Path) -> Self {
+        let files = &mut self.files;
+        for path in [
+            &mut files.left,
+            &mut files.right,
+            &mut files.specification,
+            &mut files.program,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(&mut files.user_guides)
+        .chain(&mut files.proof_outlines)
+        {
+            *path = directory.join(&*path);
+        }
+        self
+    }
+
+    pub fn roles(&self) -> Roles {
+        Roles {
+            left: self.files.left.clone(),
+            right: self.files.right.clone(),
+            specification: self.files.specification.clone(),
+            program: self.files.program.clone(),
+            user_guides: self.files.user_guides.clone(),
+            proof_outlines: self.files.proof_outlines.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{Manifest, ManifestFiles, ManifestProver},
+        crate::{
+            command_line::arguments::{Direction, Equivalence},
+            verifying::prover::vampire::VampireMode,
+        },
+        std::path::Path,
+    };
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+            equivalence = "external"
+            direction = "forward"
+
+            [files]
+            specification = "coloring.spec"
+            program = "coloring.lp"
+            user-guides = ["coloring.ug"]
+
+            [prover]
+            time-limit = 30
+            modes = ["casc", "casc_sat"]
+
+            [prover.memory-limits]
+            backward = 8192
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.relative_to(Path::new("examples")),
+            Manifest {
+                equivalence: Some(Equivalence::External),
+                decomposition: None,
+                direction: Some(Direction::Forward),
+                bypass_tightness: false,
+                natural: false,
+                files: ManifestFiles {
+                    specification: Some("examples/coloring.spec".into()),
+                    program: Some("examples/coloring.lp".into()),
+                    user_guides: vec!["examples/coloring.ug".into()],
+                    ..ManifestFiles::default()
+                },
+                prover: ManifestProver {
+                    time_limit: Some(30),
+                    modes: vec![VampireMode::Casc, VampireMode::CascSat],
+                    memory_limits: [("backward".to_string(), 8192)].into(),
+                    ..ManifestProver::default()
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_reject_unknown_settings() {
+        assert!(toml::from_str::<Manifest>("equivalence = \"weak\"").is_err());
+        assert!(toml::from_str::<Manifest>("time-limit = 30").is_err());
+    }
+}
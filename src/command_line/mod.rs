@@ -1,3 +1,5 @@
 pub mod arguments;
+pub mod batch;
 pub mod files;
+pub mod manifest;
 pub mod procedures;
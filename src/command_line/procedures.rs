@@ -3,17 +3,38 @@ use {
         analyzing::tightness::Tightness,
         command_line::{
             arguments::{
-                Arguments, Command, Equivalence, Property, SimplificationPortfolio,
-                SimplificationStrategy, Translation,
+                Arguments, Command, Equivalence, Property, ReportFormat, ReportTarget,
+                SimplificationPortfolio, SimplificationStrategy, Translation, VerifyArguments,
             },
-            files::Files,
+            batch::{self, BatchState, ExampleOutcome, Row, Success, Table, BATCH_STATE},
+            files::{read_proof_outlines, read_user_guides, Files, FilesWarning, Roles},
+            manifest::Manifest,
         },
         convenience::{apply::Apply, compose::Compose},
         simplifying::fol::{classic::CLASSIC, ht::HT, intuitionistic::INTUITIONISTIC},
         syntax_tree::{asp, fol, Node as _},
-        translating::{completion::completion, gamma::gamma, tau_star::tau_star},
+        translating::{
+            completion::completion,
+            gamma::gamma,
+            natural::natural,
+            ordered_completion::ordered_completion,
+            provenance::{
+                trace_completion, trace_gamma, trace_natural, trace_tau_star, TracedTheory,
+            },
+            tau_star::tau_star,
+            to_asp::to_asp,
+        },
         verifying::{
-            prover::{vampire::Vampire, Prover, Report, Status, Success},
+            problem::{AnnotatedFormula, Problem, Role},
+            prover::{
+                cache::{CachedProver, ProofCache},
+                intuitionistic::IntuitionisticProver,
+                portfolio::Portfolio,
+                schedule::{Schedule, Scheduled},
+                vampire::{MemoryLimit, Vampire, VampireMode},
+                Cancellation, Prover, Report, Verdict,
+            },
+            summary::{ProblemRecord, Summary},
             task::{
                 external_equivalence::ExternalEquivalenceTask,
                 strong_equivalence::StrongEquivalenceTask, Task,
@@ -23,22 +44,98 @@ use {
     anyhow::{anyhow, Context, Result},
     clap::Parser as _,
     either::Either,
-    std::time::Instant,
+    indexmap::IndexSet,
+    std::{fmt::Display, fs, path::PathBuf, process::ExitCode, time::Instant},
+    thiserror::Error,
 };
 
-pub fn main() -> Result<()> {
+/// The outcome of running anthem, which determines its exit code
+///
+/// Exit code 2 is omitted, as it is used for invalid command line arguments.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The command succeeded, for `verify` this means that all problems were proven
+    Success = 0,
+    /// The command failed for some other reason, e.g. a file could not be written
+    Failure = 1,
+    /// A prover showed that the conjectures of some problem do not follow from its axioms
+    Disproved = 3,
+    /// A prover was unable to prove or disprove some problem, e.g. because it timed out
+    Unknown = 4,
+    /// The input files are malformed or do not form a valid task
+    InvalidInput = 5,
+    /// A prover failed with an error, e.g. because it could not be run
+    ProverError = 6,
+}
+
+impl Outcome {
+    fn severity(self) -> u8 {
+        match self {
+            Outcome::Success => 0,
+            Outcome::Unknown => 1,
+            Outcome::Disproved => 2,
+            Outcome::ProverError => 3,
+            Outcome::InvalidInput => 4,
+            Outcome::Failure => 5,
+        }
+    }
+
+    /// The more severe of two outcomes, e.g. to combine the outcomes of several verifications
+    pub fn max(self, other: Outcome) -> Outcome {
+        if other.severity() > self.severity() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl From<Verdict> for Outcome {
+    fn from(verdict: Verdict) -> Self {
+        match verdict {
+            Verdict::Proved => Outcome::Success,
+            Verdict::Unknown => Outcome::Unknown,
+            Verdict::Disproved => Outcome::Disproved,
+            Verdict::Error => Outcome::ProverError,
+        }
+    }
+}
+
+impl From<&anyhow::Error> for Outcome {
+    fn from(error: &anyhow::Error) -> Self {
+        if error.is::<InvalidInput>() {
+            Outcome::InvalidInput
+        } else {
+            Outcome::Failure
+        }
+    }
+}
+
+impl From<Outcome> for ExitCode {
+    fn from(outcome: Outcome) -> Self {
+        ExitCode::from(outcome as u8)
+    }
+}
+
+/// An error caused by the input of a command rather than by anthem or the tools it runs
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct InvalidInput(anyhow::Error);
+
+pub fn main() -> Result<Outcome> {
     match Arguments::parse().command {
         Command::Analyze { property, input } => {
             match property {
                 Property::Tightness => {
-                    let program =
-                        input.map_or_else(asp::Program::from_stdin, asp::Program::from_file)?;
+                    let program = input
+                        .map_or_else(asp::Program::from_stdin, asp::Program::from_file)
+                        .map_err(InvalidInput)?;
                     let is_tight = program.is_tight();
                     println!("{is_tight}");
                 }
             }
 
-            Ok(())
+            Ok(Outcome::Success)
         }
 
         Command::Simplify {
@@ -54,7 +151,9 @@ pub fn main() -> Result<()> {
             .into_iter()
             .compose();
 
-            let theory = input.map_or_else(fol::Theory::from_stdin, fol::Theory::from_file)?;
+            let theory = input
+                .map_or_else(fol::Theory::from_stdin, fol::Theory::from_file)
+                .map_err(InvalidInput)?;
 
             let simplified_theory: fol::Theory = theory
                 .into_iter()
@@ -67,191 +166,856 @@ pub fn main() -> Result<()> {
 
             print!("{simplified_theory}");
 
-            Ok(())
+            Ok(Outcome::Success)
         }
 
-        Command::Translate { with, input } => {
+        Command::Translate { with, trace, input } => {
+            if trace
+                && matches!(
+                    with,
+                    Translation::Asp | Translation::OrderedCompletion | Translation::Tptp
+                )
+            {
+                return Err(InvalidInput(anyhow!(
+                    "tracing is only supported for completion, gamma, natural and tau-star"
+                ))
+                .into());
+            }
+
             match with {
+                Translation::Asp => {
+                    let theory = input
+                        .map_or_else(fol::Theory::from_stdin, fol::Theory::from_file)
+                        .map_err(InvalidInput)?;
+                    // The program is printed to stdout, so it can be passed to clingo directly
+                    let translation = to_asp(theory);
+                    for warning in translation.warnings {
+                        eprintln!("{warning}");
+                    }
+                    print!("{}", translation.data)
+                }
+
                 Translation::Completion => {
-                    let theory =
-                        input.map_or_else(fol::Theory::from_stdin, fol::Theory::from_file)?;
-                    let completed_theory =
-                        completion(theory).context("the given theory is not completable")?;
-                    print!("{completed_theory}")
+                    let theory = input
+                        .map_or_else(fol::Theory::from_stdin, fol::Theory::from_file)
+                        .map_err(InvalidInput)?;
+                    if trace {
+                        let completed_theory = trace_completion(TracedTheory::from_theory(theory))
+                            .context("the given theory is not completable")
+                            .map_err(InvalidInput)?;
+                        print!("{completed_theory}")
+                    } else {
+                        let completed_theory = completion(theory)
+                            .context("the given theory is not completable")
+                            .map_err(InvalidInput)?;
+                        print!("{completed_theory}")
+                    }
                 }
 
                 Translation::Gamma => {
-                    let theory =
-                        input.map_or_else(fol::Theory::from_stdin, fol::Theory::from_file)?;
-                    let gamma_theory = gamma(theory);
-                    print!("{gamma_theory}")
+                    let theory = input
+                        .map_or_else(fol::Theory::from_stdin, fol::Theory::from_file)
+                        .map_err(InvalidInput)?;
+                    if trace {
+                        print!("{}", trace_gamma(TracedTheory::from_theory(theory)))
+                    } else {
+                        let gamma_theory = gamma(theory);
+                        print!("{gamma_theory}")
+                    }
+                }
+
+                Translation::Natural => {
+                    let program = input
+                        .map_or_else(asp::Program::from_stdin, asp::Program::from_file)
+                        .map_err(InvalidInput)?;
+                    if trace {
+                        print!("{}", trace_natural(program))
+                    } else {
+                        let theory = natural(program);
+                        print!("{theory}")
+                    }
+                }
+
+                Translation::OrderedCompletion => {
+                    let program = input
+                        .map_or_else(asp::Program::from_stdin, asp::Program::from_file)
+                        .map_err(InvalidInput)?;
+                    let components: Vec<IndexSet<_>> = program
+                        .recursive_components()
+                        .into_iter()
+                        .map(|component| component.into_iter().map(fol::Predicate::from).collect())
+                        .collect();
+                    let completed_theory = ordered_completion(tau_star(program), &components)
+                        .context("the given program is not completable")
+                        .map_err(InvalidInput)?;
+                    print!("{completed_theory}")
                 }
 
                 Translation::TauStar => {
-                    let program =
-                        input.map_or_else(asp::Program::from_stdin, asp::Program::from_file)?;
-                    let theory = tau_star(program);
-                    print!("{theory}")
+                    let program = input
+                        .map_or_else(asp::Program::from_stdin, asp::Program::from_file)
+                        .map_err(InvalidInput)?;
+                    if trace {
+                        print!("{}", trace_tau_star(program))
+                    } else {
+                        let theory = tau_star(program);
+                        print!("{theory}")
+                    }
+                }
+
+                Translation::Tptp => {
+                    // Specifications, proof outlines and user guides are recognized by their extensions
+                    let extension = input
+                        .as_ref()
+                        .and_then(|path| path.extension())
+                        .and_then(|extension| extension.to_str())
+                        .map(str::to_string);
+                    let input = match (extension.as_deref(), input) {
+                        // Plain theories are often stored in `.spec` files as well
+                        (Some("spec" | "po"), Some(path)) => {
+                            match fol::Specification::from_file(&path) {
+                                Ok(specification) => Either::Left(specification),
+                                Err(error) => Either::Right(
+                                    fol::Theory::from_file(&path)
+                                        .map_err(|_| InvalidInput(error))?,
+                                ),
+                            }
+                        }
+                        (Some("ug"), Some(path)) => {
+                            let user_guide =
+                                fol::UserGuide::from_file(path).map_err(InvalidInput)?;
+                            let placeholders = user_guide
+                                .placeholders()
+                                .into_iter()
+                                .map(|p| (p.name.clone(), p))
+                                .collect();
+                            Either::Left(
+                                user_guide
+                                    .formulas()
+                                    .into_iter()
+                                    .map(|formula| formula.replace_placeholders(&placeholders))
+                                    .collect(),
+                            )
+                        }
+                        (_, input) => Either::Right(
+                            input
+                                .map_or_else(fol::Theory::from_stdin, fol::Theory::from_file)
+                                .map_err(InvalidInput)?,
+                        ),
+                    };
+
+                    let problem = match input {
+                        Either::Left(specification) => {
+                            Problem::with_name("translation").add_specification(specification)
+                        }
+                        Either::Right(theory) => {
+                            Problem::with_name("translation").add_theory(theory, |_, formula| {
+                                AnnotatedFormula {
+                                    name: "axiom".to_string(),
+                                    role: Role::Axiom,
+                                    formula: formula.universal_closure(),
+                                    provenance: None,
+                                }
+                            })
+                        }
+                    };
+
+                    print!(
+                        "{}",
+                        problem
+                            .rename_conflicting_symbols()
+                            .create_unique_formula_names()
+                    )
                 }
             }
 
-            Ok(())
+            Ok(Outcome::Success)
         }
 
-        Command::Verify {
-            equivalence,
-            decomposition,
-            direction,
-            bypass_tightness,
-            no_simplify,
-            no_eq_break,
-            no_proof_search,
-            no_timing,
-            time_limit,
-            prover_instances,
-            prover_cores,
-            save_problems: out_dir,
-            files,
-        } => {
-            let start_time = Instant::now();
-
-            let files =
-                Files::sort(files).context("unable to sort the given files by their function")?;
-
-            let problems = match equivalence {
-                Equivalence::Strong => StrongEquivalenceTask {
-                    left: asp::Program::from_file(
-                        files
-                            .left()
-                            .ok_or(anyhow!("no left program was provided"))?,
-                    )?,
-                    right: asp::Program::from_file(
-                        files
-                            .right()
-                            .ok_or(anyhow!("no right program was provided"))?,
-                    )?,
+        Command::Verify(arguments) => {
+            let arguments = *arguments;
+            // Stop all running provers on the first Ctrl-C, give up immediately on the second one
+            let cancellation = Cancellation::new();
+            if !arguments.no_proof_search {
+                let handler = cancellation.clone();
+                ctrlc::set_handler(move || {
+                    if handler.is_cancelled() {
+                        std::process::exit(130)
+                    }
+                    eprintln!("> Cancelling all running provers...");
+                    handler.cancel()
+                })
+                .context("unable to install a handler for Ctrl-C")?;
+            }
+
+            match arguments.batch.clone() {
+                Some(directory) => verify_batch(directory, arguments, &cancellation),
+                None => {
+                    let report = arguments.report.clone();
+                    let summary = verify(arguments, &cancellation)?;
+                    write_reports(&summary, report)?;
+
+                    // Without proof search, the verdict of the empty summary is `Proved`
+                    Ok(summary.verdict.into())
+                }
+            }
+        }
+    }
+}
+
+fn verify(arguments: VerifyArguments, cancellation: &Cancellation) -> Result<Summary> {
+    let VerifyArguments {
+        equivalence,
+        decomposition,
+        direction,
+        bypass_tightness,
+        natural,
+        intuitionistic,
+        no_simplify,
+        no_eq_break,
+        no_proof_search,
+        no_countermodel_search,
+        show_proofs,
+        no_timing,
+        time_limit,
+        grace_period,
+        prover_instances,
+        prover_cores,
+        memory_limit,
+        vampire_binary,
+        intuitionistic_prover,
+        vampire_mode,
+        vampire_argument,
+        fail_fast,
+        no_cache,
+        cache_dir,
+        save_problems: out_dir,
+        left,
+        right,
+        spec,
+        program,
+        user_guide,
+        outline,
+        files,
+        report: _,
+        batch: _,
+        no_resume: _,
+    } = arguments;
+
+    let start_time = Instant::now();
+
+    // Settings given on the command line take precedence over those of a manifest
+    let manifest = Manifest::find(&files)
+        .and_then(|path| path.map(Manifest::from_file).transpose())
+        .map_err(InvalidInput)?
+        .unwrap_or_default();
+
+    let equivalence = equivalence.or(manifest.equivalence).ok_or_else(|| {
+        InvalidInput(anyhow!(
+            "no equivalence was given, neither by `--equivalence` nor by a manifest"
+        ))
+    })?;
+    if intuitionistic && equivalence != Equivalence::Strong {
+        return Err(InvalidInput(anyhow!(
+            "`--intuitionistic` is only supported for strong equivalence"
+        ))
+        .into());
+    }
+    // Vampire is a classical prover, which would accept conjectures that do not hold in here-and-there
+    if intuitionistic && !no_proof_search && intuitionistic_prover.is_none() {
+        return Err(InvalidInput(anyhow!(
+            "`--intuitionistic` requires an intuitionistic prover -- pass one by `--intuitionistic-prover` or omit proof search by `--no-proof-search`"
+        ))
+        .into());
+    }
+    let decomposition = decomposition.or(manifest.decomposition).unwrap_or_default();
+    let direction = direction.or(manifest.direction).unwrap_or_default();
+    let bypass_tightness = bypass_tightness || manifest.bypass_tightness;
+    let natural = natural || manifest.natural;
+    let time_limit = time_limit.or(manifest.prover.time_limit).unwrap_or(60);
+    let grace_period = grace_period.or(manifest.prover.grace_period).unwrap_or(5);
+    let prover_instances = prover_instances.or(manifest.prover.instances).unwrap_or(1);
+    let prover_cores = prover_cores.or(manifest.prover.cores).unwrap_or(1);
+    let memory_limit: Vec<_> = manifest
+        .prover
+        .memory_limit
+        .map(|mebibytes| MemoryLimit {
+            problem: None,
+            mebibytes,
+        })
+        .into_iter()
+        .chain(
+            manifest
+                .prover
+                .memory_limits
+                .iter()
+                .map(|(problem, &mebibytes)| MemoryLimit {
+                    problem: Some(problem.clone()),
+                    mebibytes,
+                }),
+        )
+        .chain(memory_limit)
+        .collect();
+    let vampire_mode = match (vampire_mode.is_empty(), manifest.prover.modes.is_empty()) {
+        (false, _) => vampire_mode,
+        (true, false) => manifest.prover.modes.clone(),
+        (true, true) => vec![VampireMode::default()],
+    };
+    let vampire_argument = [manifest.prover.arguments.clone(), vampire_argument].concat();
+
+    let roles = Roles {
+        left,
+        right,
+        specification: spec,
+        program,
+        user_guides: user_guide,
+        proof_outlines: outline,
+    }
+    .or(manifest.roles());
+
+    // Any error up to the decomposition of the task is caused by invalid input
+    let task = (|| -> Result<_> {
+        let files = Files::sort(files)
+            .context("unable to sort the given files by their function")?
+            .assign(roles);
+
+        Ok(match equivalence {
+            Equivalence::Strong => {
+                let left = files
+                    .left()
+                    .ok_or(anyhow!("no left program was provided"))?;
+                let right = files
+                    .right()
+                    .ok_or(anyhow!("no right program was provided"))?;
+
+                let warnings = ignored_files(&files, &[left, right]);
+
+                StrongEquivalenceTask {
+                    left: asp::Program::from_file(left)?,
+                    right: asp::Program::from_file(right)?,
                     decomposition,
                     direction,
+                    natural,
+                    intuitionistic,
                     simplify: !no_simplify,
                     break_equivalences: !no_eq_break,
                 }
                 .decompose()?
-                .report_warnings(),
-                Equivalence::External => ExternalEquivalenceTask {
-                    specification: match files
-                        .specification()
-                        .ok_or(anyhow!("no specification was provided"))?
-                    {
+                .stringify_warnings()
+                .preface_warnings(warnings)
+            }
+            Equivalence::External => {
+                let specification = files
+                    .specification()
+                    .ok_or(anyhow!("no specification was provided"))?;
+                let program = files.program().ok_or(anyhow!("no program was provided"))?;
+                if files.user_guides().is_empty() {
+                    return Err(anyhow!("no user guide was provided"));
+                }
+
+                let used: Vec<_> = [specification.into_inner(), program]
+                    .into_iter()
+                    .chain(files.user_guides())
+                    .chain(files.proof_outlines())
+                    .collect();
+                let mut warnings = ignored_files(&files, &used);
+
+                let user_guide = read_user_guides(files.user_guides())?;
+                let proof_outline = read_proof_outlines(files.proof_outlines())?;
+                warnings.extend(
+                    user_guide
+                        .warnings
+                        .iter()
+                        .chain(&proof_outline.warnings)
+                        .map(ToString::to_string),
+                );
+
+                ExternalEquivalenceTask {
+                    specification: match specification {
                         Either::Left(program) => Either::Left(asp::Program::from_file(program)?),
                         Either::Right(specification) => {
                             Either::Right(fol::Specification::from_file(specification)?)
                         }
                     },
-                    program: asp::Program::from_file(
-                        files.program().ok_or(anyhow!("no program was provided"))?,
-                    )?,
-                    user_guide: fol::UserGuide::from_file(
-                        files
-                            .user_guide()
-                            .ok_or(anyhow!("no user guide was provided"))?,
-                    )?,
-                    proof_outline: files
-                        .proof_outline()
-                        .map(fol::Specification::from_file)
-                        .unwrap_or_else(|| Ok(fol::Specification::empty()))?,
+                    program: asp::Program::from_file(program)?,
+                    user_guide: user_guide.data,
+                    proof_outline: proof_outline.data,
                     decomposition,
                     direction,
                     bypass_tightness,
+                    natural,
                     simplify: !no_simplify,
                     break_equivalences: !no_eq_break,
                 }
                 .decompose()?
-                .report_warnings(),
-            };
-
-            if let Some(out_dir) = out_dir {
-                for problem in &problems {
-                    let mut path = out_dir.clone();
-                    path.push(format!("{}.p", problem.name));
-                    problem.to_file(path)?;
-                }
+                .stringify_warnings()
+                .preface_warnings(warnings)
             }
+        })
+    })()
+    .map_err(InvalidInput)?;
+    let mut summary = Summary::new(task.warnings.clone());
+    let problems = task.report_warnings();
 
-            if !no_proof_search {
-                let prover = Vampire {
-                    time_limit,
-                    instances: prover_instances,
-                    cores: prover_cores,
-                };
-
-                let problems = problems.into_iter().inspect(|problem| {
-                    println!("> Proving {}...", problem.name);
-                    println!("Axioms:");
-                    for axiom in problem.axioms() {
-                        println!("    {}", axiom.formula);
-                    }
-                    println!();
-                    println!("Conjectures:");
-                    for conjecture in problem.conjectures() {
-                        println!("    {}", conjecture.formula);
+    if let Some(out_dir) = out_dir {
+        for problem in &problems {
+            let mut path = out_dir.clone();
+            path.push(format!("{}.p", problem.name));
+            // Problems are saved in the format they are passed to the prover
+            if intuitionistic {
+                fs::write(&path, problem.to_fof()?)
+                    .with_context(|| format!("could not write file `{}`", path.display()))?;
+            } else {
+                problem.to_file(path)?;
+            }
+        }
+    }
+
+    if !no_proof_search {
+        let mut vampires: Vec<_> = vampire_mode
+            .into_iter()
+            .map(|mode| Vampire {
+                binary: vampire_binary.clone(),
+                mode,
+                time_limit,
+                grace_period,
+                memory_limits: memory_limit.clone(),
+                arguments: vampire_argument.clone(),
+                instances: prover_instances,
+                cores: prover_cores,
+            })
+            .collect();
+
+        let options = ProofSearchOptions {
+            // Vampire's casc_sat mode includes its finite model builder, and as every intuitionistic
+            // theorem is a classical one, its countermodels refute intuitionistic problems as well
+            model_finder: (!no_countermodel_search).then(|| Vampire {
+                mode: VampireMode::CascSat,
+                ..vampires[0].clone()
+            }),
+            show_proofs,
+            no_timing,
+            fail_fast,
+        };
+
+        let cache = (!no_cache)
+            .then(|| cache_dir.or_else(ProofCache::default_directory))
+            .flatten()
+            .map(ProofCache::new);
+
+        if let Some(binary) = intuitionistic_prover.filter(|_| intuitionistic) {
+            prove_all(
+                CachedProver {
+                    prover: IntuitionisticProver {
+                        binary,
+                        time_limit,
+                        grace_period,
+                        instances: prover_instances,
+                        cores: prover_cores,
+                    },
+                    cache: cache.clone(),
+                },
+                problems,
+                &mut summary,
+                &options,
+                cancellation,
+            )?
+        } else if vampires.len() == 1 {
+            prove_all(
+                CachedProver {
+                    prover: vampires.pop().unwrap(),
+                    cache: cache.clone(),
+                },
+                problems,
+                &mut summary,
+                &options,
+                cancellation,
+            )?
+        } else {
+            prove_all(
+                CachedProver {
+                    prover: Portfolio {
+                        members: vampires,
+                        instances: prover_instances,
+                    },
+                    cache: cache.clone(),
+                },
+                problems,
+                &mut summary,
+                &options,
+                cancellation,
+            )?
+        };
+
+        match summary.verdict {
+            Verdict::Proved => print!("> Success! Anthem found a proof of equivalence."),
+            Verdict::Unknown => {
+                print!("> Failure! Anthem was unable to find a proof of equivalence.")
+            }
+            Verdict::Disproved => print!(
+                "> Failure! Anthem found a problem whose conjectures do not follow from its axioms."
+            ),
+            Verdict::Error => {
+                print!("> Failure! A prover failed with an error on at least one problem.")
+            }
+        }
+
+        if !no_timing {
+            print!(" ({} ms)", start_time.elapsed().as_millis())
+        }
+
+        println!();
+
+        if let Some(cache) = cache {
+            let statistics = cache.statistics();
+            println!("> Cache: {statistics}");
+            summary.cache = Some(statistics);
+        }
+    }
+
+    summary.finish(start_time.elapsed());
+
+    Ok(summary)
+}
+
+fn verify_batch(
+    directory: PathBuf,
+    arguments: VerifyArguments,
+    cancellation: &Cancellation,
+) -> Result<Outcome> {
+    let start_time = Instant::now();
+
+    let examples = batch::discover(&directory).map_err(InvalidInput)?;
+    let state_path = directory.join(BATCH_STATE);
+    let mut state = BatchState::from_file(&state_path)?;
+
+    // A success is only reused if none of the settings that may change the verdict changed
+    let settings = format!(
+        "{:?} {:?} {:?} {} {} {} {:?} {} {}",
+        arguments.equivalence,
+        arguments.decomposition,
+        arguments.direction,
+        arguments.bypass_tightness,
+        arguments.natural,
+        arguments.intuitionistic,
+        arguments.intuitionistic_prover,
+        arguments.no_simplify,
+        arguments.no_eq_break,
+    );
+    let resume = !arguments.no_resume && !arguments.no_proof_search;
+
+    let mut rows = vec![];
+    let mut summary = Summary::new(vec![]);
+    let mut outcome = Outcome::Success;
+    for example in examples {
+        if cancellation.is_cancelled() {
+            break;
+        }
+
+        let fingerprint = example.fingerprint(&settings)?;
+        if let Some(success) = state.success(&example, &fingerprint).filter(|_| resume) {
+            println!(
+                "> Skipping {}, which is unchanged since its last success",
+                example.name
+            );
+            println!();
+            rows.push(Row {
+                example: example.name,
+                outcome: ExampleOutcome::Unchanged(success.clone()),
+            });
+            continue;
+        }
+
+        println!("> Verifying {}...", example.name);
+        let save_problems = arguments
+            .save_problems
+            .as_ref()
+            .map(|out_dir| out_dir.join(&example.name));
+        if let Some(out_dir) = &save_problems {
+            fs::create_dir_all(out_dir)
+                .with_context(|| format!("could not create directory `{}`", out_dir.display()))?;
+        }
+
+        match verify(
+            VerifyArguments {
+                files: example.files.clone(),
+                save_problems,
+                ..arguments.clone()
+            },
+            cancellation,
+        ) {
+            Ok(example_summary) => {
+                if !arguments.no_proof_search {
+                    if example_summary.verdict == Verdict::Proved {
+                        state.successes.insert(
+                            example.name.clone(),
+                            Success {
+                                fingerprint,
+                                problems: example_summary.problems.len(),
+                            },
+                        );
+                    } else {
+                        state.successes.remove(&example.name);
                     }
-                    println!();
+                    state.to_file(&state_path)?;
+                }
+
+                outcome = outcome.max(example_summary.verdict.into());
+                summary.extend(&example.name, example_summary.clone());
+                rows.push(Row {
+                    example: example.name,
+                    outcome: ExampleOutcome::Verified(example_summary),
+                });
+            }
+            Err(error) => {
+                eprintln!("Error: {error:?}");
+                let failure = Outcome::from(&error);
+                outcome = outcome.max(failure);
+                rows.push(Row {
+                    example: example.name,
+                    outcome: ExampleOutcome::Failed(match failure {
+                        Outcome::InvalidInput => "invalid input".to_string(),
+                        _ => "error".to_string(),
+                    }),
                 });
+            }
+        }
+        println!();
+    }
 
-                let mut success = true;
-                for result in prover.prove_all(problems) {
-                    match result {
-                        Ok(report) => match report.status() {
-                            Ok(status) => {
-                                println!(
-                                    "> Proving {} ended with a SZS status",
-                                    report.problem.name
-                                );
-                                print!("Status: {status}");
-                                if !no_timing {
-                                    print!(" ({} ms)", report.elapsed_time.as_millis())
-                                }
-                                println!();
-                                if !matches!(status, Status::Success(Success::Theorem)) {
-                                    success = false;
-                                }
-                            }
-                            Err(error) => {
-                                println!(
-                                    "> Proving {} ended without a SZS status",
-                                    report.problem.name
-                                );
-                                println!("Output/stdout:");
-                                println!("{}", report.output.stdout);
-                                println!("Output/stderr:");
-                                println!("{}", report.output.stderr);
-                                println!("Error: {error}");
-                                success = false;
+    println!("> Summary of {}", directory.display());
+    print!("{}", Table(&rows));
+
+    summary.finish(start_time.elapsed());
+    write_reports(&summary, arguments.report)?;
+
+    Ok(outcome)
+}
+
+fn write_reports(summary: &Summary, targets: Vec<ReportTarget>) -> Result<()> {
+    for target in targets {
+        match target.format {
+            ReportFormat::Json => summary.to_json_file(target.path)?,
+            ReportFormat::Junit => summary.to_junit_file(target.path)?,
+        }
+    }
+    Ok(())
+}
+
+/// The options of `prove_all` besides the prover and problems
+struct ProofSearchOptions {
+    /// The prover used to find countermodels of problems whose conjectures do not follow from their axioms
+    model_finder: Option<Vampire>,
+    show_proofs: bool,
+    no_timing: bool,
+    fail_fast: bool,
+}
+
+fn prove_all<P>(
+    prover: P,
+    problems: Vec<Problem>,
+    summary: &mut Summary,
+    options: &ProofSearchOptions,
+    cancellation: &Cancellation,
+) -> Result<()>
+where
+    P: Prover,
+    P::Error: Display,
+{
+    // Under sequential decomposition, the conjectures of earlier problems become lemmas of later ones
+    let conjectures: IndexSet<String> = problems
+        .iter()
+        .flat_map(|problem| problem.conjectures())
+        .map(|conjecture| conjecture.name)
+        .collect();
+
+    // Results arrive in the order of the problems, so each problem is printed alongside its result
+    let results = Schedule::new(problems).run(&prover, options.fail_fast, cancellation.clone());
+    for (problem, result) in results {
+        println!("> Proving {}...", problem.name);
+        println!("Axioms:");
+        for axiom in problem.axioms() {
+            println!("    {}", axiom.formula);
+        }
+        println!();
+        println!("Conjectures:");
+        for conjecture in problem.conjectures() {
+            println!("    {}", conjecture.formula);
+            if let Some(provenance) = conjecture.provenance {
+                for line in provenance.to_string().lines() {
+                    println!("        % {line}");
+                }
+            }
+        }
+        println!();
+
+        match result {
+            Scheduled::Skipped(premises) => {
+                println!(
+                    "> Skipping {}, as the conjectures of {} were not proven",
+                    problem.name,
+                    premises.join(", ")
+                );
+                summary.add(ProblemRecord::skipped(&problem, premises));
+            }
+            Scheduled::Finished(Ok(report)) => {
+                summary.add(ProblemRecord::from_report(&report));
+                match report.status() {
+                    Ok(status) => {
+                        println!(
+                            "> Proving {} ended with a SZS status",
+                            report.problem().name
+                        );
+                        print!("Status: {status}");
+                        if report.cached() {
+                            print!(" (cached)");
+                        } else if !options.no_timing {
+                            print!(" ({} ms)", report.elapsed_time().as_millis())
+                        }
+                        println!();
+                        if status.proves_conjecture() {
+                            print_used_axioms(&report, &conjectures);
+                            if options.show_proofs {
+                                print_proof(&report);
                             }
-                        },
-                        Err(error) => {
-                            println!("> Proving <a problem> ended with an error"); // TODO: Get the name of the problem
-                            println!("Error: {error}");
-                            success = false;
                         }
+                        if status.disproves_conjecture() {
+                            print_countermodel(
+                                &report,
+                                options.model_finder.as_ref(),
+                                cancellation,
+                            );
+                        }
+                    }
+                    Err(error) => {
+                        println!(
+                            "> Proving {} ended without a SZS status",
+                            report.problem().name
+                        );
+                        println!("Output/stdout:");
+                        println!("{}", report.stdout());
+                        println!("Output/stderr:");
+                        println!("{}", report.stderr());
+                        println!("Error: {error}");
                     }
-                    println!();
                 }
+            }
+            Scheduled::Finished(Err(error)) => {
+                println!("> Proving {} ended with an error", problem.name);
+                println!("Error: {error}");
+                summary.add(ProblemRecord::from_error(&problem, error));
+            }
+        }
+        println!();
+    }
 
-                if success {
-                    print!("> Success! Anthem found a proof of equivalence.")
-                } else {
-                    print!("> Failure! Anthem was unable to find a proof of equivalence.")
-                }
+    Ok(())
+}
 
-                if !no_timing {
-                    print!(" ({} ms)", start_time.elapsed().as_millis())
-                }
+fn ignored_files(files: &Files, used: &[&PathBuf]) -> Vec<String> {
+    files
+        .ignored(used)
+        .into_iter()
+        .map(|path| FilesWarning::IgnoredFile(path.clone()).to_string())
+        .collect()
+}
+
+fn print_used_axioms(report: &impl Report, lemmas: &IndexSet<String>) {
+    let Some(used_axioms) = report.used_axioms() else {
+        return;
+    };
 
-                println!()
+    let mut completed_definitions = vec![];
+    let mut loop_formulas = vec![];
+    let mut used_lemmas = vec![];
+    let mut assumptions = vec![];
+    for axiom in report.problem().axioms() {
+        if !used_axioms.contains(&axiom.name) {
+            continue;
+        }
+
+        if lemmas.contains(&axiom.name) {
+            used_lemmas.push(axiom.name)
+        } else if axiom.name.contains("completed_definition_of_") {
+            completed_definitions.push(axiom.name)
+        } else if axiom.name.contains("loop_formula_") {
+            loop_formulas.push(axiom.name)
+        } else {
+            assumptions.push(axiom.name)
+        }
+    }
+
+    for (title, names) in [
+        ("Used completed definitions", completed_definitions),
+        ("Used loop formulas", loop_formulas),
+        ("Used lemmas", used_lemmas),
+        ("Used assumptions", assumptions),
+    ] {
+        if !names.is_empty() {
+            println!("{title}:");
+            for name in names {
+                println!("    {name}");
             }
+        }
+    }
+}
+
+fn print_countermodel(
+    report: &impl Report,
+    model_finder: Option<&Vampire>,
+    cancellation: &Cancellation,
+) {
+    let countermodel = report.countermodel().or_else(|| {
+        model_finder?
+            .prove_cancellable(report.problem().clone(), cancellation)
+            .ok()?
+            .countermodel()
+    });
 
-            Ok(())
+    match countermodel {
+        Some(countermodel) => {
+            println!("Countermodel:");
+            for line in countermodel.to_string().lines() {
+                println!("    {line}");
+            }
         }
+        None if model_finder.is_some() => println!("No countermodel was found."),
+        None => (),
+    }
+}
+
+fn print_proof(report: &impl Report) {
+    match report.proof() {
+        Some(proof) => {
+            println!("Proof:");
+            for line in proof.to_string().lines() {
+                println!("    {line}");
+            }
+        }
+        None => println!("The prover did not print a proof."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{InvalidInput, Outcome},
+        crate::verifying::prover::Verdict,
+        anyhow::anyhow,
+        std::process::ExitCode,
+    };
+
+    #[test]
+    fn test_outcome() {
+        assert_eq!(Outcome::from(Verdict::Proved), Outcome::Success);
+        assert_eq!(Outcome::from(Verdict::Disproved), Outcome::Disproved);
+        assert_eq!(Outcome::from(Verdict::Error), Outcome::ProverError);
+
+        let error = anyhow::Error::from(InvalidInput(anyhow!("no program was provided")));
+        assert_eq!(error.to_string(), "no program was provided");
+        assert_eq!(Outcome::from(&error), Outcome::InvalidInput);
+        assert_eq!(Outcome::from(&anyhow!("disk full")), Outcome::Failure);
+
+        assert_eq!(ExitCode::from(Outcome::Unknown), ExitCode::from(4));
     }
 }
@@ -27,6 +27,13 @@ impl<D, W> WithWarnings<D, W> {
 }
 
 impl<D, W: Display> WithWarnings<D, W> {
+    pub fn stringify_warnings(self) -> WithWarnings<D, String> {
+        WithWarnings {
+            data: self.data,
+            warnings: self.warnings.iter().map(ToString::to_string).collect(),
+        }
+    }
+
     pub fn report_warnings(self) -> D {
         for warning in self.warnings {
             println!("{warning}");
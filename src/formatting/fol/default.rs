@@ -907,7 +907,7 @@ mod tests {
         assert_eq!(
             Format(&Theory {
                 formulas: vec![
-                    Formula::AtomicFormula(AtomicFormula::Truth).into(),
+                    Formula::AtomicFormula(AtomicFormula::Truth),
                     Formula::BinaryFormula {
                         connective: BinaryConnective::Equivalence,
                         lhs: Formula::AtomicFormula(AtomicFormula::Atom(Atom {
@@ -0,0 +1,230 @@
+use {
+    crate::{
+        formatting::{fol::tptp, Associativity, Precedence},
+        syntax_tree::{
+            fol::{
+                Atom, AtomicFormula, Comparison, Formula, GeneralTerm, Quantification, Relation,
+                Sort, SymbolicTerm,
+            },
+            Node,
+        },
+    },
+    std::fmt::{self, Display, Formatter},
+};
+
+/// The untyped first-order logic of TPTP, as read by intuitionistic provers such as ileanCoP and nanoCoP
+///
+/// Only formulas for which `supports` holds can be formatted, the others fail with an error.
+pub struct Format<'a, N: Node>(pub &'a N);
+
+/// Whether a formula can be formatted without sorts
+///
+/// This excludes integer and symbolic variables, numerals, function constants, the infimum and supremum and all comparisons except equality.
+pub fn supports(formula: &Formula) -> bool {
+    fn term(term: &GeneralTerm) -> bool {
+        matches!(
+            term,
+            GeneralTerm::Variable(_) | GeneralTerm::SymbolicTerm(SymbolicTerm::Symbol(_))
+        )
+    }
+
+    match formula {
+        Formula::AtomicFormula(AtomicFormula::Truth | AtomicFormula::Falsity) => true,
+        Formula::AtomicFormula(AtomicFormula::Atom(a)) => a.terms.iter().all(term),
+        Formula::AtomicFormula(AtomicFormula::Comparison(c)) => {
+            term(&c.term)
+                && c.guards.iter().all(|g| {
+                    matches!(g.relation, Relation::Equal | Relation::NotEqual) && term(&g.term)
+                })
+        }
+        Formula::UnaryFormula { formula, .. } => supports(formula),
+        Formula::BinaryFormula { lhs, rhs, .. } => supports(lhs) && supports(rhs),
+        Formula::QuantifiedFormula {
+            quantification,
+            formula,
+        } => {
+            quantification
+                .variables
+                .iter()
+                .all(|v| v.sort == Sort::General)
+                && supports(formula)
+        }
+    }
+}
+
+impl Display for Format<'_, GeneralTerm> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            GeneralTerm::Variable(v) => write!(f, "{v}_g"),
+            GeneralTerm::SymbolicTerm(SymbolicTerm::Symbol(s)) => write!(f, "{s}"),
+            _ => Err(fmt::Error),
+        }
+    }
+}
+
+impl Display for Format<'_, Atom> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let predicate = &self.0.predicate_symbol;
+        let terms = &self.0.terms;
+
+        write!(f, "{predicate}")?;
+
+        if !terms.is_empty() {
+            let mut iter = terms.iter().map(Format);
+            write!(f, "({}", iter.next().unwrap())?;
+            for term in iter {
+                write!(f, ", {term}")?;
+            }
+            write!(f, ")")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Format<'_, Comparison> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (counter, (lhs, relation, rhs)) in self.0.individuals().enumerate() {
+            if counter > 0 {
+                write!(f, " & ")?;
+            }
+
+            match relation {
+                Relation::Equal | Relation::NotEqual => {
+                    write!(
+                        f,
+                        "{} {} {}",
+                        Format(lhs),
+                        tptp::Format(relation),
+                        Format(rhs)
+                    )
+                }
+                _ => Err(fmt::Error),
+            }?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Format<'_, AtomicFormula> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            AtomicFormula::Truth => write!(f, "$true"),
+            AtomicFormula::Falsity => write!(f, "$false"),
+            AtomicFormula::Atom(a) => Format(a).fmt(f),
+            AtomicFormula::Comparison(c) => Format(c).fmt(f),
+        }
+    }
+}
+
+impl Display for Format<'_, Quantification> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[", tptp::Format(&self.0.quantifier))?;
+
+        for (counter, var) in self.0.variables.iter().enumerate() {
+            if counter > 0 {
+                write!(f, ", ")?;
+            }
+            match var.sort {
+                Sort::General => write!(f, "{}", tptp::Format(var)),
+                Sort::Integer | Sort::Symbol => Err(fmt::Error),
+            }?;
+        }
+
+        write!(f, "]")
+    }
+}
+
+impl Precedence for Format<'_, Formula> {
+    fn precedence(&self) -> usize {
+        match self.0 {
+            Formula::AtomicFormula(_) => 0,
+            Formula::UnaryFormula { .. } => 1,
+            Formula::QuantifiedFormula { .. } => 2,
+            Formula::BinaryFormula { .. } => 3,
+        }
+    }
+
+    fn associativity(&self) -> Associativity {
+        Associativity::Left
+    }
+
+    fn mandatory_parentheses(&self) -> bool {
+        match self.0 {
+            Formula::AtomicFormula(_) | Formula::QuantifiedFormula { .. } => false,
+            Formula::UnaryFormula { .. } | Formula::BinaryFormula { .. } => true,
+        }
+    }
+
+    fn fmt_operator(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Formula::UnaryFormula { connective, .. } => write!(f, "{}", tptp::Format(connective)),
+            Formula::BinaryFormula { connective, .. } => {
+                write!(f, " {} ", tptp::Format(connective))
+            }
+            Formula::QuantifiedFormula { quantification, .. } => {
+                write!(f, "{}: ", Format(quantification))
+            }
+            Formula::AtomicFormula(_) => unreachable!(),
+        }
+    }
+}
+
+impl Display for Format<'_, Formula> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Formula::AtomicFormula(a) => Format(a).fmt(f),
+            Formula::UnaryFormula { formula, .. } => self.fmt_unary(Format(formula.as_ref()), f),
+            Formula::QuantifiedFormula {
+                quantification,
+                formula,
+            } => {
+                let connective = Format(quantification);
+                let formula = Format(formula.as_ref());
+                write!(f, "{connective}: ({formula})")
+            }
+            Formula::BinaryFormula { lhs, rhs, .. } => {
+                self.fmt_binary(Format(lhs.as_ref()), Format(rhs.as_ref()), f)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{supports, Format},
+        crate::syntax_tree::fol::Formula,
+    };
+
+    #[test]
+    fn format_formula() {
+        for (src, target) in [
+            (
+                "forall V1 X (V1 = X and exists Z (Z = X and not q(Z, a)) -> p(V1))",
+                "![V1_g, X_g]: ((V1_g = X_g & ?[Z_g]: (Z_g = X_g & (~q(Z_g, a)))) => p(V1_g))",
+            ),
+            ("p or (q -> #false)", "p | (q => $false)"),
+            ("X != Y", "X_g != Y_g"),
+        ] {
+            let formula: Formula = src.parse().unwrap();
+            assert!(supports(&formula));
+            assert_eq!(Format(&formula).to_string(), target);
+        }
+    }
+
+    #[test]
+    fn supports_formula() {
+        for src in [
+            "p(1)",
+            "forall N$i p(N$i)",
+            "forall X (p(X) -> X < a)",
+            "p(n$g)",
+            "exists S$s p(S$s)",
+        ] {
+            let formula: Formula = src.parse().unwrap();
+            assert!(!supports(&formula), "{formula}");
+        }
+    }
+}
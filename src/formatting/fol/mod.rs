@@ -1,2 +1,3 @@
 pub mod default;
+pub mod fof;
 pub mod tptp;
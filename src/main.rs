@@ -9,8 +9,14 @@ pub mod syntax_tree;
 pub mod translating;
 pub mod verifying;
 
-use anyhow::Result;
+use {crate::command_line::procedures::Outcome, std::process::ExitCode};
 
-fn main() -> Result<()> {
-    crate::command_line::procedures::main()
+fn main() -> ExitCode {
+    match crate::command_line::procedures::main() {
+        Ok(outcome) => outcome.into(),
+        Err(error) => {
+            eprintln!("Error: {error:?}");
+            Outcome::from(&error).into()
+        }
+    }
 }
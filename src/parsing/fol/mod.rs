@@ -1 +1,2 @@
 pub mod pest;
+pub mod tptp;
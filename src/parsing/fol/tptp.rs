@@ -0,0 +1,447 @@
+use {
+    crate::{
+        parsing::Parser,
+        syntax_tree::fol::{
+            Atom, AtomicFormula, BinaryConnective, BinaryOperator, Comparison, Formula,
+            GeneralTerm, Guard, IntegerTerm, Quantification, Quantifier, Relation, Sort,
+            SymbolicTerm, UnaryConnective, UnaryOperator, Variable,
+        },
+    },
+    indexmap::IndexMap,
+    pest::iterators::Pair,
+    thiserror::Error,
+};
+
+mod internal {
+    use pest::pratt_parser::PrattParser;
+
+    #[derive(pest_derive::Parser)]
+    #[grammar = "parsing/fol/tptp.pest"]
+    pub struct Parser;
+
+    lazy_static::lazy_static! {
+        pub static ref FORMULA_PRATT_PARSER: PrattParser<Rule> = {
+            use pest::pratt_parser::{Assoc::*, Op};
+            use Rule::*;
+
+            PrattParser::new()
+                .op(Op::infix(equivalence, Right)
+                    | Op::infix(exclusive_disjunction, Right)
+                    | Op::infix(implication, Right)
+                    | Op::infix(reverse_implication, Left))
+                .op(Op::infix(disjunction, Left) | Op::infix(negated_disjunction, Left))
+                .op(Op::infix(conjunction, Left) | Op::infix(negated_conjunction, Left))
+                .op(Op::prefix(negation) | Op::prefix(quantification))
+        };
+    }
+}
+
+pub use internal::Rule;
+
+#[derive(Debug, Error)]
+pub enum TptpError {
+    #[error("unable to parse the TPTP formula")]
+    Syntax(#[source] Box<pest::error::Error<Rule>>),
+    #[error("the TPTP term `{0}` has no counterpart in anthem's syntax")]
+    UntranslatableTerm(String),
+    #[error("the TPTP formula `{0}` has no counterpart in anthem's syntax")]
+    UntranslatableFormula(String),
+}
+
+/// Parses a TPTP formula and undoes the encoding of `formatting::fol::tptp`
+///
+/// Besides formulas written by anthem, this accepts formulas as they appear in proofs of provers,
+/// e.g. with renamed variables or additional connectives.
+pub struct FormulaParser;
+
+impl Parser for FormulaParser {
+    type Node = Formula;
+    type Error = TptpError;
+
+    fn parse<S: AsRef<str>>(input: S) -> Result<Self::Node, Self::Error> {
+        use pest::Parser as _;
+
+        let mut pairs = internal::Parser::parse(Rule::formula_eoi, input.as_ref())
+            .map_err(|e| TptpError::Syntax(Box::new(e)))?;
+        translate_formula(pairs.next().unwrap(), &IndexMap::new())
+    }
+}
+
+/// A term together with the sort TPTP assigns to it
+enum SortedTerm {
+    General(GeneralTerm),
+    Integer(IntegerTerm),
+    Symbolic(SymbolicTerm),
+}
+
+impl SortedTerm {
+    fn into_general(self) -> GeneralTerm {
+        match self {
+            SortedTerm::General(t) => t,
+            SortedTerm::Integer(t) => GeneralTerm::IntegerTerm(t),
+            SortedTerm::Symbolic(t) => GeneralTerm::SymbolicTerm(t),
+        }
+    }
+
+    fn into_integer(self, pair: &Pair<'_, Rule>) -> Result<IntegerTerm, TptpError> {
+        match self {
+            SortedTerm::Integer(t) | SortedTerm::General(GeneralTerm::IntegerTerm(t)) => Ok(t),
+            _ => Err(TptpError::UntranslatableTerm(pair.as_str().into())),
+        }
+    }
+
+    fn into_symbolic(self, pair: &Pair<'_, Rule>) -> Result<SymbolicTerm, TptpError> {
+        match self {
+            SortedTerm::Symbolic(t) | SortedTerm::General(GeneralTerm::SymbolicTerm(t)) => Ok(t),
+            _ => Err(TptpError::UntranslatableTerm(pair.as_str().into())),
+        }
+    }
+}
+
+/// Split a name such as `X_g` or `n_i` into its anthem name and sort
+fn split_sort(name: &str) -> Option<(&str, Sort)> {
+    if let Some(name) = name.strip_suffix("_g") {
+        Some((name, Sort::General))
+    } else if let Some(name) = name.strip_suffix("_i") {
+        Some((name, Sort::Integer))
+    } else if let Some(name) = name.strip_suffix("_s") {
+        Some((name, Sort::Symbol))
+    } else {
+        None
+    }
+}
+
+fn translate_variable(name: &str, sort: Sort) -> Variable {
+    let name = match split_sort(name) {
+        Some((stripped, suffix)) if suffix == sort => stripped,
+        _ => name,
+    };
+    Variable {
+        name: name.into(),
+        sort,
+    }
+}
+
+fn translate_term(
+    pair: Pair<'_, Rule>,
+    scope: &IndexMap<String, Sort>,
+) -> Result<SortedTerm, TptpError> {
+    let untranslatable = || TptpError::UntranslatableTerm(pair.as_str().into());
+
+    let mut pairs = pair.clone().into_inner();
+    let head = pairs.next().unwrap();
+
+    match head.as_rule() {
+        Rule::numeral => Ok(SortedTerm::Integer(IntegerTerm::Numeral(
+            head.as_str().parse().map_err(|_| untranslatable())?,
+        ))),
+        Rule::variable => {
+            let sort = scope
+                .get(head.as_str())
+                .cloned()
+                .or_else(|| split_sort(head.as_str()).map(|(_, sort)| sort))
+                .unwrap_or(Sort::General);
+            let variable = translate_variable(head.as_str(), sort);
+            Ok(match variable.sort {
+                Sort::General => SortedTerm::General(GeneralTerm::Variable(variable.name)),
+                Sort::Integer => SortedTerm::Integer(IntegerTerm::Variable(variable.name)),
+                Sort::Symbol => SortedTerm::Symbolic(SymbolicTerm::Variable(variable.name)),
+            })
+        }
+        Rule::functor => {
+            let arguments: Vec<_> = pairs.collect();
+            let functor = head.as_str();
+
+            let integer_operation = |op| -> Result<SortedTerm, TptpError> {
+                let [lhs, rhs] = arguments.as_slice() else {
+                    return Err(untranslatable());
+                };
+                Ok(SortedTerm::Integer(IntegerTerm::BinaryOperation {
+                    op,
+                    lhs: Box::new(translate_term(lhs.clone(), scope)?.into_integer(lhs)?),
+                    rhs: Box::new(translate_term(rhs.clone(), scope)?.into_integer(rhs)?),
+                }))
+            };
+
+            match (functor, arguments.as_slice()) {
+                ("c__infimum__", []) => Ok(SortedTerm::General(GeneralTerm::Infimum)),
+                ("c__supremum__", []) => Ok(SortedTerm::General(GeneralTerm::Supremum)),
+                ("f__integer__", [argument]) => Ok(SortedTerm::General(GeneralTerm::IntegerTerm(
+                    translate_term(argument.clone(), scope)?.into_integer(argument)?,
+                ))),
+                ("f__symbolic__", [argument]) => {
+                    Ok(SortedTerm::General(GeneralTerm::SymbolicTerm(
+                        translate_term(argument.clone(), scope)?.into_symbolic(argument)?,
+                    )))
+                }
+                ("$sum", _) => integer_operation(BinaryOperator::Add),
+                ("$difference", _) => integer_operation(BinaryOperator::Subtract),
+                ("$product", _) => integer_operation(BinaryOperator::Multiply),
+                ("$uminus", [argument]) => Ok(SortedTerm::Integer(IntegerTerm::UnaryOperation {
+                    op: UnaryOperator::Negative,
+                    arg: Box::new(translate_term(argument.clone(), scope)?.into_integer(argument)?),
+                })),
+                (constant, []) if !constant.starts_with('$') => Ok(match split_sort(constant) {
+                    Some((name, Sort::General)) => {
+                        SortedTerm::General(GeneralTerm::FunctionConstant(name.into()))
+                    }
+                    Some((name, Sort::Integer)) => {
+                        SortedTerm::Integer(IntegerTerm::FunctionConstant(name.into()))
+                    }
+                    Some((name, Sort::Symbol)) => {
+                        SortedTerm::Symbolic(SymbolicTerm::FunctionConstant(name.into()))
+                    }
+                    None => SortedTerm::Symbolic(SymbolicTerm::Symbol(constant.into())),
+                }),
+                _ => Err(untranslatable()),
+            }
+        }
+        _ => unreachable!("in {:?}: unexpected pair found: {head}", Rule::term),
+    }
+}
+
+fn translate_atomic_formula(
+    pair: Pair<'_, Rule>,
+    scope: &IndexMap<String, Sort>,
+) -> Result<Formula, TptpError> {
+    let untranslatable = || TptpError::UntranslatableFormula(pair.as_str().into());
+    let inner = pair.clone().into_inner().next().unwrap();
+
+    let comparison = |lhs: GeneralTerm, relation, rhs| {
+        Formula::AtomicFormula(AtomicFormula::Comparison(Comparison {
+            term: lhs,
+            guards: vec![Guard {
+                relation,
+                term: rhs,
+            }],
+        }))
+    };
+
+    match inner.as_rule() {
+        Rule::equation => {
+            let mut pairs = inner.into_inner();
+            let lhs = translate_term(pairs.next().unwrap(), scope)?.into_general();
+            let relation = match pairs.next().unwrap().as_rule() {
+                Rule::equal => Relation::Equal,
+                _ => Relation::NotEqual,
+            };
+            let rhs = translate_term(pairs.next().unwrap(), scope)?.into_general();
+            Ok(comparison(lhs, relation, rhs))
+        }
+        Rule::term => {
+            let mut pairs = inner.into_inner();
+            let predicate = pairs.next().unwrap();
+            let arguments: Vec<_> = pairs.collect();
+
+            let relation = match predicate.as_str() {
+                "$true" if arguments.is_empty() => {
+                    return Ok(Formula::AtomicFormula(AtomicFormula::Truth))
+                }
+                "$false" if arguments.is_empty() => {
+                    return Ok(Formula::AtomicFormula(AtomicFormula::Falsity))
+                }
+                "$less" | "p__less__" => Some(Relation::Less),
+                "$lesseq" | "p__less_equal__" => Some(Relation::LessEqual),
+                "$greater" | "p__greater__" => Some(Relation::Greater),
+                "$greatereq" | "p__greater_equal__" => Some(Relation::GreaterEqual),
+                symbol if symbol.starts_with('$') || symbol.starts_with("p__") => {
+                    return Err(untranslatable())
+                }
+                _ => None,
+            };
+
+            let mut terms = vec![];
+            for argument in arguments {
+                terms.push(translate_term(argument, scope)?.into_general());
+            }
+
+            match relation {
+                Some(relation) => {
+                    let [lhs, rhs]: [GeneralTerm; 2] =
+                        terms.try_into().map_err(|_| untranslatable())?;
+                    Ok(comparison(lhs, relation, rhs))
+                }
+                None => Ok(Formula::AtomicFormula(AtomicFormula::Atom(Atom {
+                    predicate_symbol: predicate.as_str().into(),
+                    terms,
+                }))),
+            }
+        }
+        _ => unreachable!(
+            "in {:?}: unexpected pair found: {inner}",
+            Rule::atomic_formula
+        ),
+    }
+}
+
+fn translate_quantification(
+    pair: Pair<'_, Rule>,
+) -> Result<(Quantification, Vec<(String, Sort)>), TptpError> {
+    let mut pairs = pair.clone().into_inner();
+    let quantifier = match pairs.next().unwrap().as_rule() {
+        Rule::forall => Quantifier::Forall,
+        _ => Quantifier::Exists,
+    };
+
+    let mut variables = vec![];
+    let mut bindings = vec![];
+    for typed_variable in pairs {
+        let mut pairs = typed_variable.into_inner();
+        let name = pairs.next().unwrap().as_str();
+        let sort = match pairs.next().map(|pair| pair.as_str()) {
+            Some("general") | None => Sort::General,
+            Some("$int") => Sort::Integer,
+            Some("symbol") => Sort::Symbol,
+            Some(_) => return Err(TptpError::UntranslatableFormula(pair.as_str().into())),
+        };
+        bindings.push((name.to_string(), sort.clone()));
+        variables.push(translate_variable(name, sort));
+    }
+
+    Ok((
+        Quantification {
+            quantifier,
+            variables,
+        },
+        bindings,
+    ))
+}
+
+fn negate(formula: Formula) -> Formula {
+    Formula::UnaryFormula {
+        connective: UnaryConnective::Negation,
+        formula: Box::new(formula),
+    }
+}
+
+/// The structure of a formula before its atoms are translated
+///
+/// Translating atoms requires the sorts of the variables bound by enclosing quantifiers,
+/// so they can only be translated top-down after the operator precedence has been resolved.
+enum Structure<'i> {
+    Atomic(Pair<'i, Rule>),
+    Prefixed(Pair<'i, Rule>, Box<Structure<'i>>),
+    Infixed(Pair<'i, Rule>, Box<Structure<'i>>, Box<Structure<'i>>),
+}
+
+fn structure(pair: Pair<'_, Rule>) -> Structure<'_> {
+    internal::FORMULA_PRATT_PARSER
+        .map_primary(|primary| match primary.as_rule() {
+            Rule::formula => structure(primary),
+            Rule::atomic_formula => Structure::Atomic(primary),
+            _ => unreachable!("in {:?}: unexpected pair found: {primary}", Rule::formula),
+        })
+        .map_prefix(|op, arg| Structure::Prefixed(op, Box::new(arg)))
+        .map_infix(|lhs, op, rhs| Structure::Infixed(op, Box::new(lhs), Box::new(rhs)))
+        .parse(pair.into_inner())
+}
+
+fn translate_formula(
+    pair: Pair<'_, Rule>,
+    scope: &IndexMap<String, Sort>,
+) -> Result<Formula, TptpError> {
+    translate_structure(structure(pair), scope)
+}
+
+fn translate_structure(
+    structure: Structure<'_>,
+    scope: &IndexMap<String, Sort>,
+) -> Result<Formula, TptpError> {
+    match structure {
+        Structure::Atomic(pair) => translate_atomic_formula(pair, scope),
+        Structure::Prefixed(op, arg) => match op.as_rule() {
+            Rule::negation => Ok(negate(translate_structure(*arg, scope)?)),
+            Rule::quantification => {
+                let (quantification, bindings) = translate_quantification(op)?;
+                let mut scope = scope.clone();
+                scope.extend(bindings);
+                Ok(Formula::QuantifiedFormula {
+                    quantification,
+                    formula: Box::new(translate_structure(*arg, &scope)?),
+                })
+            }
+            _ => unreachable!("in {:?}: unexpected pair found: {op}", Rule::formula),
+        },
+        Structure::Infixed(op, lhs, rhs) => {
+            let lhs = translate_structure(*lhs, scope)?;
+            let rhs = translate_structure(*rhs, scope)?;
+            let binary = |connective, lhs, rhs| Formula::BinaryFormula {
+                connective,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            };
+            Ok(match op.as_rule() {
+                Rule::equivalence => binary(BinaryConnective::Equivalence, lhs, rhs),
+                Rule::exclusive_disjunction => {
+                    negate(binary(BinaryConnective::Equivalence, lhs, rhs))
+                }
+                Rule::implication => binary(BinaryConnective::Implication, lhs, rhs),
+                Rule::reverse_implication => binary(BinaryConnective::ReverseImplication, lhs, rhs),
+                Rule::negated_disjunction => {
+                    negate(binary(BinaryConnective::Disjunction, lhs, rhs))
+                }
+                Rule::negated_conjunction => {
+                    negate(binary(BinaryConnective::Conjunction, lhs, rhs))
+                }
+                Rule::conjunction => binary(BinaryConnective::Conjunction, lhs, rhs),
+                Rule::disjunction => binary(BinaryConnective::Disjunction, lhs, rhs),
+                _ => unreachable!("in {:?}: unexpected pair found: {op}", Rule::formula),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::FormulaParser,
+        crate::{formatting::fol::tptp::Format, parsing::Parser as _, syntax_tree::fol::Formula},
+    };
+
+    #[test]
+    fn parse_formula() {
+        for (tptp, anthem) in [
+            ("p", "p"),
+            ("$true & ~$false", "#true and not #false"),
+            (
+                "![X_g: general, N_i: $int]: (p(X_g, f__integer__(N_i)) => q(X_g))",
+                "forall X N$i (p(X, N$i) -> q(X))",
+            ),
+            (
+                "? [X0 : $int] : (f__integer__(X0) = n_g & $less(X0, $sum(3, $uminus(m_i))))",
+                "exists X0$i (X0$i = n$g and X0$i < 3 + -m$i)",
+            ),
+            (
+                "p__less_equal__(c__infimum__, f__symbolic__(a)) | X0 != a_s",
+                "#inf <= a or X0 != a$s",
+            ),
+            ("p <~> q", "not (p <-> q)"),
+            ("p ~| q", "not (p or q)"),
+        ] {
+            let expected: Formula = anthem.parse().unwrap();
+            assert_eq!(FormulaParser::parse(tptp).unwrap(), expected, "{tptp}");
+        }
+    }
+
+    #[test]
+    fn parse_formula_roundtrip() {
+        let formula: Formula =
+            "forall X Y$i (p(X, Y$i + 1) <- exists Z$s (q(Z$s, #sup) and X > n$i))"
+                .parse()
+                .unwrap();
+        let tptp = Format(&formula).to_string();
+        assert_eq!(FormulaParser::parse(tptp).unwrap(), formula);
+    }
+
+    #[test]
+    fn reject_untranslatable_formula() {
+        for tptp in [
+            "p(sK0(X0))",
+            "p__is_integer__(X0)",
+            "$is_int(X0)",
+            "p(X0) &",
+        ] {
+            assert!(FormulaParser::parse(tptp).is_err(), "{tptp}");
+        }
+    }
+}
@@ -982,6 +982,7 @@ impl AnnotatedFormula {
             name: self.name,
             role,
             formula: self.formula,
+            provenance: None,
         }
     }
 
@@ -5,16 +5,35 @@ use {
     },
     indexmap::{map::Entry, IndexMap},
     itertools::Itertools,
+    thiserror::Error,
 };
 
-pub fn completion(theory: fol::Theory) -> Option<fol::Theory> {
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CompletionError {
+    #[error("the following formula has the free variables {}: {formula} -- bind them by a universal quantifier, e.g. `forall {} (...)`", .variables.iter().join(", "), .variables.iter().join(" "))]
+    FreeVariables {
+        formula: fol::Formula,
+        variables: Vec<fol::Variable>,
+    },
+    #[error("the following formula is not an implication: {0} -- write it as a rule `forall X (F -> p(X))` or as a constraint `forall X (F -> #false)`")]
+    NotAnImplication(fol::Formula),
+    #[error("the head of the following formula is neither an atom nor #false: {0} -- rewrite it such that the right-hand side of the implication is a single atom or #false")]
+    NonAtomicHead(fol::Formula),
+    #[error("the arguments of the head of the following formula are not distinct variables: {0} -- replace every other argument `t` by a fresh variable `V` and add `V = t` to the body")]
+    InvalidHeadArguments(fol::Formula),
+    #[error("the heads {} of the predicate `{predicate}` do not match -- rename the variables of every head to those of `{}`", .heads.iter().map(|head| format!("`{head}`")).join(", "), .heads[0])]
+    HeadMismatch {
+        predicate: fol::Predicate,
+        heads: Vec<fol::AtomicFormula>,
+    },
+}
+
+pub fn completion(theory: fol::Theory) -> Result<fol::Theory, CompletionError> {
     // Retrieve the definitions and constraints
     let (definitions, constraints) = components(theory)?;
 
     // Confirm there are no head mismatches
-    if has_head_mismatches(&definitions) {
-        return None;
-    }
+    ensure_matching_heads(&definitions)?;
 
     // Complete the definitions
     let completed_definitions = definitions.into_iter().map(|(g, a)| {
@@ -37,16 +56,19 @@ pub fn completion(theory: fol::Theory) -> Option<fol::Theory> {
         .collect();
     formulas.extend(completed_definitions);
 
-    Some(fol::Theory { formulas })
+    Ok(fol::Theory { formulas })
 }
 
-pub(crate) fn has_head_mismatches(definitions: &Definitions) -> bool {
-    for (_, heads) in heads(definitions) {
+pub(crate) fn ensure_matching_heads(definitions: &Definitions) -> Result<(), CompletionError> {
+    for (predicate, heads) in heads(definitions) {
         if !heads.iter().all_equal() {
-            return true;
+            return Err(CompletionError::HeadMismatch {
+                predicate,
+                heads: heads.into_iter().cloned().collect(),
+            });
         }
     }
-    false
+    Ok(())
 }
 
 fn heads(definitions: &Definitions) -> IndexMap<fol::Predicate, Vec<&fol::AtomicFormula>> {
@@ -68,7 +90,9 @@ fn heads(definitions: &Definitions) -> IndexMap<fol::Predicate, Vec<&fol::Atomic
     result
 }
 
-pub(crate) fn components(theory: fol::Theory) -> Option<(Definitions, Constraints)> {
+pub(crate) fn components(
+    theory: fol::Theory,
+) -> Result<(Definitions, Constraints), CompletionError> {
     let mut definitions: Definitions = IndexMap::new();
     let mut constraints = Vec::new();
 
@@ -86,31 +110,39 @@ pub(crate) fn components(theory: fol::Theory) -> Option<(Definitions, Constraint
         }
     }
 
-    Some((definitions, constraints))
+    Ok((definitions, constraints))
 }
 
-type Definitions = IndexMap<fol::AtomicFormula, Vec<fol::Formula>>;
-type Constraints = Vec<fol::Formula>;
+pub(crate) type Definitions = IndexMap<fol::AtomicFormula, Vec<fol::Formula>>;
+pub(crate) type Constraints = Vec<fol::Formula>;
 
-fn split(formula: fol::Formula) -> Option<Component> {
-    if !formula.free_variables().is_empty() {
-        return None;
+fn split(formula: fol::Formula) -> Result<Component, CompletionError> {
+    let variables = formula.free_variables();
+    if !variables.is_empty() {
+        return Err(CompletionError::FreeVariables {
+            formula,
+            variables: variables.into_iter().collect(),
+        });
     }
 
-    match formula {
+    // Errors refer to the formula as it was given, including its quantifier
+    match formula.clone() {
         fol::Formula::QuantifiedFormula {
             quantification:
                 fol::Quantification {
                     quantifier: fol::Quantifier::Forall,
                     ..
                 },
-            formula,
-        } => split_implication(*formula),
-        formula => split_implication(formula),
+            formula: inner,
+        } => split_implication(*inner),
+        inner => split_implication(inner),
     }
+    .map_err(|error| error(formula))
 }
 
-fn split_implication(formula: fol::Formula) -> Option<Component> {
+fn split_implication(
+    formula: fol::Formula,
+) -> Result<Component, fn(fol::Formula) -> CompletionError> {
     match formula.clone().unbox() {
         UnboxedFormula::BinaryFormula {
             connective: fol::BinaryConnective::Implication,
@@ -124,7 +156,7 @@ fn split_implication(formula: fol::Formula) -> Option<Component> {
         } => match g {
             // TODO: What about fol::AtomicFormula::Truth?
             fol::Formula::AtomicFormula(fol::AtomicFormula::Falsity) => {
-                Some(Component::Constraint(formula))
+                Ok(Component::Constraint(formula))
             }
             fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(a)) => {
                 let mut v = a.terms.iter().map(|t| match t {
@@ -148,17 +180,17 @@ fn split_implication(formula: fol::Formula) -> Option<Component> {
                 });
 
                 if v.clone().contains(&None) | !v.all_unique() {
-                    None
+                    Err(CompletionError::InvalidHeadArguments)
                 } else {
-                    Some(Component::PartialDefinition {
+                    Ok(Component::PartialDefinition {
                         f,
                         a: fol::AtomicFormula::Atom(a),
                     })
                 }
             }
-            _ => None,
+            _ => Err(CompletionError::NonAtomicHead),
         },
-        _ => None,
+        _ => Err(CompletionError::NotAnImplication),
     }
 }
 
@@ -174,7 +206,10 @@ enum Component {
 mod tests {
     use crate::{
         syntax_tree::fol,
-        translating::{completion::completion, tau_star::tau_star},
+        translating::{
+            completion::{completion, CompletionError},
+            tau_star::tau_star,
+        },
     };
 
     #[test]
@@ -210,9 +245,50 @@ mod tests {
         ] {
             let theory: fol::Theory = theory.parse().unwrap();
             assert!(
-                completion(theory.clone()).is_none(),
+                completion(theory.clone()).is_err(),
                 "`{theory}` should not be completable"
             );
         }
     }
+
+    #[test]
+    fn test_completion_errors() {
+        let formula = |src: &str| -> fol::Formula { src.parse().unwrap() };
+        for (theory, error) in [
+            (
+                "p(X) <- q(X).",
+                CompletionError::FreeVariables {
+                    formula: formula("p(X) <- q(X)"),
+                    variables: vec![fol::Variable {
+                        name: "X".into(),
+                        sort: fol::Sort::General,
+                    }],
+                },
+            ),
+            (
+                "forall X (p(X) <-> q(X)).",
+                CompletionError::NotAnImplication(formula("forall X (p(X) <-> q(X))")),
+            ),
+            (
+                "forall X (q(X) -> p(X) or r(X)).",
+                CompletionError::NonAtomicHead(formula("forall X (q(X) -> p(X) or r(X))")),
+            ),
+            (
+                "forall X (p(X, a) <- q(X)).",
+                CompletionError::InvalidHeadArguments(formula("forall X (p(X, a) <- q(X))")),
+            ),
+            (
+                "forall V1 V2 (p(V1, V2) <- t). forall V1 X (p(V1, X) <- q).",
+                CompletionError::HeadMismatch {
+                    predicate: fol::Predicate {
+                        symbol: "p".into(),
+                        arity: 2,
+                    },
+                    heads: vec!["p(V1, V2)".parse().unwrap(), "p(V1, X)".parse().unwrap()],
+                },
+            ),
+        ] {
+            assert_eq!(completion(theory.parse().unwrap()), Err(error));
+        }
+    }
 }
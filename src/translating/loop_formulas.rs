@@ -0,0 +1,301 @@
+use {
+    crate::{
+        syntax_tree::fol,
+        translating::completion::{self, completion, CompletionError},
+    },
+    indexmap::{IndexMap, IndexSet},
+};
+
+/// Extend the completion of a theory by the loop formulas of the given recursive components
+pub fn completion_with_loop_formulas(
+    theory: fol::Theory,
+    components: &[IndexSet<fol::Predicate>],
+) -> Result<fol::Theory, CompletionError> {
+    let mut completed_theory = completion(theory.clone())?;
+    completed_theory
+        .formulas
+        .extend(loop_formulas(theory, components)?.formulas);
+    Ok(completed_theory)
+}
+
+/// Create the loop formulas of the given recursive components of a completable theory
+///
+/// The loops of a first-order program are unbounded, e.g. the loops of a transitive closure grow with its domain,
+/// so loop formulas cannot be listed one by one. Instead, every predicate `p/n` of a recursive component is given
+/// a ranking `rank_p/n+1` by non-negative integers, such that every atom `p(V)` with a rank `N` is supported by a
+/// rule whose positive body atoms of the same component have ranks below `N`:
+///
+///   forall V (p(V) -> exists N$i rank_p(V, N$i))
+///   forall V N$i (rank_p(V, N$i) -> N$i >= 0 and F)
+///
+/// where `F` is the right-hand side of the completed definition of `p/n` in which every positive occurrence of an
+/// atom `q(T)` of the same component is replaced by `q(T) and exists M$i (M$i < N$i and rank_q(T, M$i))`.
+/// As the ranks are well-founded, the models of the completion and the loop formulas are exactly those in which
+/// no set of atoms supports itself, i.e. whose restrictions to the predicates of the theory are answer sets.
+pub fn loop_formulas(
+    theory: fol::Theory,
+    components: &[IndexSet<fol::Predicate>],
+) -> Result<fol::Theory, CompletionError> {
+    let taken = theory.predicates();
+    let (definitions, _) = completion::components(theory)?;
+
+    let mut rankings = IndexMap::new();
+    for predicate in components.iter().flatten() {
+        let mut symbol = format!("rank_{}", predicate.symbol);
+        while taken.contains(&fol::Predicate {
+            symbol: symbol.clone(),
+            arity: predicate.arity + 1,
+        }) {
+            symbol = format!("rank_{symbol}");
+        }
+        rankings.insert(predicate.clone(), symbol);
+    }
+
+    let mut formulas = vec![];
+    for component in components {
+        for (head, bodies) in &definitions {
+            let fol::AtomicFormula::Atom(atom) = head else {
+                unreachable!()
+            };
+            if !component.contains(&atom.predicate()) {
+                continue;
+            }
+
+            let v = head.variables();
+            let mut names: IndexSet<String> = v.iter().map(|v| v.name.clone()).collect();
+            for body in bodies {
+                names.extend(variable_names(body));
+            }
+            let n = fresh_variable("N", &mut names);
+            let m = fresh_variable("M", &mut names);
+
+            let ranked = |atom: &fol::Atom, rank: &str| {
+                let mut terms = atom.terms.clone();
+                terms.push(fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Variable(
+                    rank.to_string(),
+                )));
+                fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(fol::Atom {
+                    predicate_symbol: rankings[&atom.predicate()].clone(),
+                    terms,
+                }))
+            };
+
+            formulas.push(
+                fol::Formula::BinaryFormula {
+                    connective: fol::BinaryConnective::Implication,
+                    lhs: fol::Formula::AtomicFormula(head.clone()).into(),
+                    rhs: ranked(atom, &n)
+                        .quantify(fol::Quantifier::Exists, vec![integer_variable(&n)])
+                        .into(),
+                }
+                .quantify(fol::Quantifier::Forall, v.iter().cloned().collect()),
+            );
+
+            let support = fol::Formula::disjoin(bodies.iter().map(|f_i| {
+                let u_i = f_i.free_variables().difference(&v).cloned().collect();
+                replace_positive_atoms(f_i.clone(), component, &|atom: &fol::Atom| {
+                    fol::Formula::conjoin([
+                        fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(atom.clone())),
+                        fol::Formula::conjoin([
+                            comparison(&m, fol::Relation::Less, &n),
+                            ranked(atom, &m),
+                        ])
+                        .quantify(fol::Quantifier::Exists, vec![integer_variable(&m)]),
+                    ])
+                })
+                .quantify(fol::Quantifier::Exists, u_i)
+            }));
+
+            formulas.push(
+                fol::Formula::BinaryFormula {
+                    connective: fol::BinaryConnective::Implication,
+                    lhs: ranked(atom, &n).into(),
+                    rhs: fol::Formula::conjoin([
+                        fol::Formula::AtomicFormula(fol::AtomicFormula::Comparison(
+                            fol::Comparison {
+                                term: integer_term(&n),
+                                guards: vec![fol::Guard {
+                                    relation: fol::Relation::GreaterEqual,
+                                    term: fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Numeral(
+                                        0,
+                                    )),
+                                }],
+                            },
+                        )),
+                        support,
+                    ])
+                    .into(),
+                }
+                .quantify(
+                    fol::Quantifier::Forall,
+                    v.iter().cloned().chain([integer_variable(&n)]).collect(),
+                ),
+            );
+        }
+    }
+
+    Ok(fol::Theory { formulas })
+}
+
+/// Replace every positive occurrence of an atom of the given predicates, i.e. every occurrence not in the scope of a negation or in an antecedent
+pub(crate) fn replace_positive_atoms(
+    formula: fol::Formula,
+    predicates: &IndexSet<fol::Predicate>,
+    replacement: &impl Fn(&fol::Atom) -> fol::Formula,
+) -> fol::Formula {
+    match formula {
+        fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(atom))
+            if predicates.contains(&atom.predicate()) =>
+        {
+            replacement(&atom)
+        }
+        fol::Formula::BinaryFormula {
+            connective:
+                connective @ (fol::BinaryConnective::Conjunction | fol::BinaryConnective::Disjunction),
+            lhs,
+            rhs,
+        } => fol::Formula::BinaryFormula {
+            connective,
+            lhs: replace_positive_atoms(*lhs, predicates, replacement).into(),
+            rhs: replace_positive_atoms(*rhs, predicates, replacement).into(),
+        },
+        fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Implication,
+            lhs,
+            rhs,
+        } => fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Implication,
+            lhs,
+            rhs: replace_positive_atoms(*rhs, predicates, replacement).into(),
+        },
+        fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::ReverseImplication,
+            lhs,
+            rhs,
+        } => fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::ReverseImplication,
+            lhs: replace_positive_atoms(*lhs, predicates, replacement).into(),
+            rhs,
+        },
+        fol::Formula::QuantifiedFormula {
+            quantification,
+            formula,
+        } => fol::Formula::QuantifiedFormula {
+            quantification,
+            formula: replace_positive_atoms(*formula, predicates, replacement).into(),
+        },
+        formula => formula,
+    }
+}
+
+/// The names of all variables of a formula, including those that are quantified but do not occur
+pub(crate) fn variable_names(formula: &fol::Formula) -> IndexSet<String> {
+    let mut names: IndexSet<_> = formula.variables().into_iter().map(|v| v.name).collect();
+    match formula {
+        fol::Formula::AtomicFormula(_) => (),
+        fol::Formula::UnaryFormula { formula, .. } => names.extend(variable_names(formula)),
+        fol::Formula::BinaryFormula { lhs, rhs, .. } => {
+            names.extend(variable_names(lhs));
+            names.extend(variable_names(rhs));
+        }
+        fol::Formula::QuantifiedFormula {
+            quantification,
+            formula,
+        } => {
+            names.extend(quantification.variables.iter().map(|v| v.name.clone()));
+            names.extend(variable_names(formula));
+        }
+    }
+    names
+}
+
+pub(crate) fn fresh_variable(name: &str, taken: &mut IndexSet<String>) -> String {
+    let fresh = (0..)
+        .map(|i| match i {
+            0 => name.to_string(),
+            i => format!("{name}{i}"),
+        })
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap();
+    taken.insert(fresh.clone());
+    fresh
+}
+
+fn integer_variable(name: &str) -> fol::Variable {
+    fol::Variable {
+        name: name.to_string(),
+        sort: fol::Sort::Integer,
+    }
+}
+
+fn integer_term(name: &str) -> fol::GeneralTerm {
+    fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Variable(name.to_string()))
+}
+
+fn comparison(lhs: &str, relation: fol::Relation, rhs: &str) -> fol::Formula {
+    fol::Formula::AtomicFormula(fol::AtomicFormula::Comparison(fol::Comparison {
+        term: integer_term(lhs),
+        guards: vec![fol::Guard {
+            relation,
+            term: integer_term(rhs),
+        }],
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{completion_with_loop_formulas, loop_formulas},
+        crate::{
+            analyzing::tightness::Tightness,
+            syntax_tree::{asp, fol},
+            translating::tau_star::tau_star,
+        },
+        indexmap::IndexSet,
+    };
+
+    fn components(program: &asp::Program) -> Vec<IndexSet<fol::Predicate>> {
+        program
+            .recursive_components()
+            .into_iter()
+            .map(|component| component.into_iter().map(fol::Predicate::from).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_loop_formulas() {
+        for (src, target) in [
+            ("p(X) :- q(X).", ""),
+            (
+                "p :- p.",
+                "p -> exists N$i rank_p(N$i). forall N$i (rank_p(N$i) -> N$i >= 0 and (p and exists M$i (M$i < N$i and rank_p(M$i)))).",
+            ),
+            (
+                "p :- q, not r. p :- r. r :- p. rank_p(1).",
+                "p -> exists N$i rank_rank_p(N$i). forall N$i (rank_rank_p(N$i) -> N$i >= 0 and ((q and not r) or (r and exists M$i (M$i < N$i and rank_r(M$i))))). r -> exists N$i rank_r(N$i). forall N$i (rank_r(N$i) -> N$i >= 0 and (p and exists M$i (M$i < N$i and rank_rank_p(M$i)))).",
+            ),
+            (
+                "t(X, Y) :- e(X, Y). t(X, Z) :- e(X, Y), t(Y, Z).",
+                "forall V1 V2 (t(V1, V2) -> exists N$i rank_t(V1, V2, N$i)). forall V1 V2 N$i (rank_t(V1, V2, N$i) -> N$i >= 0 and (exists X Y (V1 = X and V2 = Y and exists Z Z1 (Z = X and Z1 = Y and e(Z, Z1))) or exists X Z Y (V1 = X and V2 = Z and (exists Z Z1 (Z = X and Z1 = Y and e(Z, Z1)) and exists Z1 Z2 (Z1 = Y and Z2 = Z and (t(Z1, Z2) and exists M$i (M$i < N$i and rank_t(Z1, Z2, M$i))))))))."
+            ),
+        ] {
+            let program: asp::Program = src.parse().unwrap();
+            let left = loop_formulas(tau_star(program.clone()), &components(&program)).unwrap();
+            let right: fol::Theory = target.parse().unwrap();
+
+            assert!(
+                left == right,
+                "assertion `left == right` failed:\n left:\n{left}\n right:\n{right}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_completion_with_loop_formulas() {
+        let program: asp::Program = "a :- b. b :- a. b :- c.".parse().unwrap();
+        let theory =
+            completion_with_loop_formulas(tau_star(program.clone()), &components(&program))
+                .unwrap();
+        assert_eq!(theory.formulas.len(), 6);
+    }
+}
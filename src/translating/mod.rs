@@ -1,3 +1,8 @@
 pub mod completion;
 pub mod gamma;
+pub mod loop_formulas;
+pub mod natural;
+pub mod ordered_completion;
+pub mod provenance;
 pub mod tau_star;
+pub mod to_asp;
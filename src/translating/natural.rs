@@ -0,0 +1,235 @@
+use {
+    crate::{
+        syntax_tree::{asp, fol},
+        translating::tau_star::{choose_fresh_global_variables, tau_star_rule},
+    },
+    indexmap::IndexMap,
+};
+
+/// Translate a program into formulas in a natural way
+///
+/// Rules whose terms are variables and precomputed terms only, i.e. without arithmetic or intervals, are translated directly.
+/// As in `tau_star`, the head arguments are replaced by the global variables `V1, ..., Vn` to keep the result completable,
+/// but a head variable occurring for the first time becomes the global variable itself instead of being equated with it.
+/// All other rules are translated by `tau_star`, to which the result is equivalent.
+pub fn natural(program: asp::Program) -> fol::Theory {
+    let globals = choose_fresh_global_variables(&program);
+    let formulas = program
+        .rules
+        .iter()
+        .map(|rule| {
+            if is_regular(rule) {
+                natural_rule(rule, &globals)
+            } else {
+                tau_star_rule(rule, &globals)
+            }
+        })
+        .collect();
+    fol::Theory { formulas }
+}
+
+fn is_regular(rule: &asp::Rule) -> bool {
+    fn is_simple(term: &asp::Term) -> bool {
+        matches!(term, asp::Term::PrecomputedTerm(_) | asp::Term::Variable(_))
+    }
+
+    rule.head.terms().unwrap_or_default().iter().all(is_simple)
+        && rule.body.formulas.iter().all(|formula| match formula {
+            asp::AtomicFormula::Literal(literal) => literal.atom.terms.iter().all(is_simple),
+            asp::AtomicFormula::Comparison(comparison) => {
+                is_simple(&comparison.lhs) && is_simple(&comparison.rhs)
+            }
+        })
+}
+
+fn natural_term(term: &asp::Term, mapping: &IndexMap<String, String>) -> fol::GeneralTerm {
+    match term {
+        asp::Term::PrecomputedTerm(asp::PrecomputedTerm::Infimum) => fol::GeneralTerm::Infimum,
+        asp::Term::PrecomputedTerm(asp::PrecomputedTerm::Supremum) => fol::GeneralTerm::Supremum,
+        asp::Term::PrecomputedTerm(asp::PrecomputedTerm::Numeral(n)) => {
+            fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Numeral(*n))
+        }
+        asp::Term::PrecomputedTerm(asp::PrecomputedTerm::Symbol(s)) => {
+            fol::GeneralTerm::SymbolicTerm(fol::SymbolicTerm::Symbol(s.clone()))
+        }
+        asp::Term::Variable(asp::Variable(v)) => {
+            fol::GeneralTerm::Variable(mapping.get(v).unwrap_or(v).clone())
+        }
+        asp::Term::UnaryOperation { .. } | asp::Term::BinaryOperation { .. } => {
+            unreachable!("only regular rules are translated naturally")
+        }
+    }
+}
+
+fn natural_atom(atom: &asp::Atom, mapping: &IndexMap<String, String>) -> fol::Formula {
+    fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(fol::Atom {
+        predicate_symbol: atom.predicate_symbol.clone(),
+        terms: atom
+            .terms
+            .iter()
+            .map(|term| natural_term(term, mapping))
+            .collect(),
+    }))
+}
+
+fn negation(formula: fol::Formula) -> fol::Formula {
+    fol::Formula::UnaryFormula {
+        connective: fol::UnaryConnective::Negation,
+        formula: formula.into(),
+    }
+}
+
+fn natural_body_formula(
+    formula: &asp::AtomicFormula,
+    mapping: &IndexMap<String, String>,
+) -> fol::Formula {
+    match formula {
+        asp::AtomicFormula::Literal(literal) => {
+            let atom = natural_atom(&literal.atom, mapping);
+            match literal.sign {
+                asp::Sign::NoSign => atom,
+                asp::Sign::Negation => negation(atom),
+                asp::Sign::DoubleNegation => negation(negation(atom)),
+            }
+        }
+        asp::AtomicFormula::Comparison(comparison) => {
+            fol::Formula::AtomicFormula(fol::AtomicFormula::Comparison(fol::Comparison {
+                term: natural_term(&comparison.lhs, mapping),
+                guards: vec![fol::Guard {
+                    relation: match comparison.relation {
+                        asp::Relation::Equal => fol::Relation::Equal,
+                        asp::Relation::NotEqual => fol::Relation::NotEqual,
+                        asp::Relation::Greater => fol::Relation::Greater,
+                        asp::Relation::Less => fol::Relation::Less,
+                        asp::Relation::GreaterEqual => fol::Relation::GreaterEqual,
+                        asp::Relation::LessEqual => fol::Relation::LessEqual,
+                    },
+                    term: natural_term(&comparison.rhs, mapping),
+                }],
+            }))
+        }
+    }
+}
+
+// forall G V ( V = t & B -> p(V) ), where head variables occurring for the first time are renamed to their global variable
+fn natural_rule(rule: &asp::Rule, globals: &[String]) -> fol::Formula {
+    let mut mapping = IndexMap::new();
+    let mut equalities = Vec::new();
+    let head_terms = rule.head.terms().unwrap_or_default();
+    for (term, global) in head_terms.iter().zip(globals) {
+        match term {
+            asp::Term::Variable(asp::Variable(v)) if !mapping.contains_key(v) => {
+                mapping.insert(v.clone(), global.clone());
+            }
+            _ => equalities.push((term, global)),
+        }
+    }
+
+    let equalities: Vec<_> = equalities
+        .into_iter()
+        .map(|(term, global)| {
+            fol::Formula::AtomicFormula(fol::AtomicFormula::Comparison(fol::Comparison {
+                term: fol::GeneralTerm::Variable(global.clone()),
+                guards: vec![fol::Guard {
+                    relation: fol::Relation::Equal,
+                    term: natural_term(term, &mapping),
+                }],
+            }))
+        })
+        .collect();
+
+    let body = fol::Formula::conjoin(
+        equalities.into_iter().chain(
+            rule.body
+                .formulas
+                .iter()
+                .map(|formula| natural_body_formula(formula, &mapping)),
+        ),
+    );
+
+    let head = match &rule.head {
+        asp::Head::Basic(atom) | asp::Head::Choice(atom) => {
+            fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(fol::Atom {
+                predicate_symbol: atom.predicate_symbol.clone(),
+                terms: globals[..atom.terms.len()]
+                    .iter()
+                    .map(|v| fol::GeneralTerm::Variable(v.clone()))
+                    .collect(),
+            }))
+        }
+        asp::Head::Falsity => fol::Formula::AtomicFormula(fol::AtomicFormula::Falsity),
+    };
+
+    let body = match rule.head {
+        asp::Head::Choice(_) => fol::Formula::conjoin([body, negation(negation(head.clone()))]),
+        asp::Head::Basic(_) | asp::Head::Falsity => body,
+    };
+
+    let formula = fol::Formula::BinaryFormula {
+        connective: fol::BinaryConnective::Implication,
+        lhs: body.into(),
+        rhs: head.into(),
+    };
+    let mut variables: Vec<_> = formula.free_variables().into_iter().collect();
+    variables.sort();
+    formula.quantify(fol::Quantifier::Forall, variables)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::natural,
+        crate::translating::{completion::completion, tau_star::tau_star},
+    };
+
+    #[test]
+    fn test_natural() {
+        for (src, target) in [
+            ("a :- b. a :- c.", "b -> a. c -> a."),
+            ("p.", "#true -> p."),
+            ("q :- not p, not not r.", "not p and not not r -> q."),
+            ("p(a). p(X) :- q(X, Y), X < Y.", "forall V1 (V1 = a -> p(V1)). forall V1 Y (q(V1, Y) and V1 < Y -> p(V1))."),
+            ("{q(X, X)} :- p(X).", "forall V1 V2 (V2 = V1 and p(V1) and not not q(V1, V2) -> q(V1, V2))."),
+            (":- p(X, 3), not q(X, a).", "forall X (p(X, 3) and not q(X, a) -> #false)."),
+            ("p(X + 1) :- q(X).", "forall V1 X (exists I$i J$i (V1 = I$i + J$i and I$i = X and J$i = 1) and exists Z (Z = X and q(Z)) -> p(V1))."),
+            ("p(V1) :- q(V1, V2).", "forall V2 V3 (q(V3, V2) -> p(V3))."),
+        ] {
+            let left = natural(src.parse().unwrap());
+            let right = target.parse().unwrap();
+
+            assert!(
+                left == right,
+                "assertion `left == right` failed:\n left:\n{left}\n right:\n{right}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_natural_completion() {
+        for (src, target) in [
+            (
+                "p(X) :- q(X). p(a).",
+                "forall V1 (p(V1) <-> q(V1) or V1 = a).",
+            ),
+            (
+                "t(X, Y) :- e(X, Y). t(X, Z) :- e(X, Y), t(Y, Z).",
+                "forall V1 V2 (t(V1, V2) <-> e(V1, V2) or exists Y (e(V1, Y) and t(Y, V2))).",
+            ),
+        ] {
+            let left = completion(natural(src.parse().unwrap())).unwrap();
+            let right = target.parse().unwrap();
+
+            assert!(
+                left == right,
+                "assertion `left == right` failed:\n left:\n{left}\n right:\n{right}"
+            );
+        }
+
+        // Irregular rules fall back to tau_star
+        let program = "p(X..Y) :- q(X), r(Y).";
+        assert_eq!(
+            natural(program.parse().unwrap()),
+            tau_star(program.parse().unwrap())
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use {
+    crate::{
+        syntax_tree::fol,
+        translating::{
+            completion::{components, ensure_matching_heads, CompletionError},
+            loop_formulas::replace_positive_atoms,
+        },
+    },
+    indexmap::{IndexMap, IndexSet},
+};
+
+/// Create the ordered completion of a completable theory with respect to the given recursive components
+///
+/// This follows Asuncion, Lin, Zhang and Zhou (2012). The definitions of predicates outside of the components are completed as usual.
+/// For a predicate `p/n` of a component, the equivalence of its completed definition is split into both implications,
+/// where the implication from left to right additionally requires every positive occurrence of an atom `q(T)` of the same component
+/// to be derived before `p(V)`, i.e. `less_q_p(T, V) and not less_p_q(V, T)`. The comparison predicates `less_q_p/m+n` are transitive,
+/// and numbered as in `less_q_p_1` if their names are taken.
+/// On finite structures, the models of the ordered completion are exactly the answer sets of the theory.
+pub fn ordered_completion(
+    theory: fol::Theory,
+    components: &[IndexSet<fol::Predicate>],
+) -> Result<fol::Theory, CompletionError> {
+    // Symbols of different arities clash in TPTP, so only the symbols are taken into account
+    let taken: IndexSet<_> = theory.predicates().into_iter().map(|p| p.symbol).collect();
+    let (definitions, constraints) = self::components(theory)?;
+
+    ensure_matching_heads(&definitions)?;
+
+    // Names such as `less_a_b_c` may stem from several pairs, so they are numbered until they are unique
+    let mut comparisons: IndexMap<_, String> = IndexMap::new();
+    for component in components {
+        for p in component {
+            for q in component {
+                let name = format!("less_{}_{}", p.symbol, q.symbol);
+                let mut symbol = name.clone();
+                let mut counter = 1..;
+                while taken.contains(&symbol) || comparisons.values().any(|taken| *taken == symbol)
+                {
+                    symbol = format!("{name}_{}", counter.next().unwrap());
+                }
+                comparisons.insert((p.clone(), q.clone()), symbol);
+            }
+        }
+    }
+
+    let comparison = |p: &fol::Atom, q: &fol::Atom| {
+        fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(fol::Atom {
+            predicate_symbol: comparisons[&(p.predicate(), q.predicate())].clone(),
+            terms: p.terms.iter().chain(q.terms.iter()).cloned().collect(),
+        }))
+    };
+
+    let mut formulas: Vec<_> = constraints
+        .into_iter()
+        .map(fol::Formula::universal_closure)
+        .collect();
+
+    for (g, a) in definitions {
+        let fol::AtomicFormula::Atom(ref head) = g else {
+            unreachable!()
+        };
+        let v = g.variables();
+
+        let bodies = |a: Vec<fol::Formula>| {
+            fol::Formula::disjoin(a.into_iter().map(|f_i| {
+                let u_i = f_i.free_variables().difference(&v).cloned().collect();
+                f_i.quantify(fol::Quantifier::Exists, u_i)
+            }))
+        };
+
+        let Some(component) = components
+            .iter()
+            .find(|component| component.contains(&head.predicate()))
+        else {
+            formulas.push(
+                fol::Formula::BinaryFormula {
+                    connective: fol::BinaryConnective::Equivalence,
+                    lhs: fol::Formula::AtomicFormula(g.clone()).into(),
+                    rhs: bodies(a).into(),
+                }
+                .quantify(fol::Quantifier::Forall, v.iter().cloned().collect()),
+            );
+            continue;
+        };
+
+        let ordered = a
+            .iter()
+            .map(|f_i| {
+                replace_positive_atoms(f_i.clone(), component, &|atom: &fol::Atom| {
+                    fol::Formula::conjoin([
+                        fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(atom.clone())),
+                        comparison(atom, head),
+                        fol::Formula::UnaryFormula {
+                            connective: fol::UnaryConnective::Negation,
+                            formula: comparison(head, atom).into(),
+                        },
+                    ])
+                })
+            })
+            .collect();
+
+        formulas.push(
+            fol::Formula::BinaryFormula {
+                connective: fol::BinaryConnective::Implication,
+                lhs: fol::Formula::AtomicFormula(g.clone()).into(),
+                rhs: bodies(ordered).into(),
+            }
+            .quantify(fol::Quantifier::Forall, v.iter().cloned().collect()),
+        );
+        formulas.push(
+            fol::Formula::BinaryFormula {
+                connective: fol::BinaryConnective::ReverseImplication,
+                lhs: fol::Formula::AtomicFormula(g.clone()).into(),
+                rhs: bodies(a).into(),
+            }
+            .quantify(fol::Quantifier::Forall, v.iter().cloned().collect()),
+        );
+    }
+
+    for component in components {
+        for p in component {
+            for q in component {
+                for r in component {
+                    let [x, y, z] =
+                        [(p, "X"), (q, "Y"), (r, "Z")].map(|(predicate, name)| fol::Atom {
+                            predicate_symbol: predicate.symbol.clone(),
+                            terms: (1..=predicate.arity)
+                                .map(|i| fol::GeneralTerm::Variable(format!("{name}{i}")))
+                                .collect(),
+                        });
+                    formulas.push(
+                        fol::Formula::BinaryFormula {
+                            connective: fol::BinaryConnective::Implication,
+                            lhs: fol::Formula::conjoin([comparison(&x, &y), comparison(&y, &z)])
+                                .into(),
+                            rhs: comparison(&x, &z).into(),
+                        }
+                        .universal_closure(),
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(fol::Theory { formulas })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::ordered_completion,
+        crate::{
+            analyzing::tightness::Tightness,
+            syntax_tree::{asp, fol},
+            translating::tau_star::tau_star,
+        },
+        indexmap::IndexSet,
+    };
+
+    #[test]
+    fn test_ordered_completion() {
+        for (src, target) in [
+            ("p(X) :- q(X). :- p(1).", "exists Z (Z = 1 and p(Z)) -> #false. forall V1 (p(V1) <-> exists X (V1 = X and exists Z (Z = X and q(Z))))."),
+            (
+                "p :- q. q :- p. q :- not r.",
+                "p -> q and less_q_p and not less_p_q. p <- q. q -> p and less_p_q and not less_q_p or not r. q <- p or not r. less_p_p and less_p_p -> less_p_p. less_p_p and less_p_q -> less_p_q. less_p_q and less_q_p -> less_p_p. less_p_q and less_q_q -> less_p_q. less_q_p and less_p_p -> less_q_p. less_q_p and less_p_q -> less_q_q. less_q_q and less_q_p -> less_q_p. less_q_q and less_q_q -> less_q_q.",
+            ),
+            (
+                "t(X, Y) :- e(X, Y). t(X, Z) :- e(X, Y), t(Y, Z).",
+                "forall V1 V2 (t(V1, V2) -> exists X Y (V1 = X and V2 = Y and exists Z Z1 (Z = X and Z1 = Y and e(Z, Z1))) or exists X Z Y (V1 = X and V2 = Z and (exists Z Z1 (Z = X and Z1 = Y and e(Z, Z1)) and exists Z1 Z2 (Z1 = Y and Z2 = Z and (t(Z1, Z2) and less_t_t(Z1, Z2, V1, V2) and not less_t_t(V1, V2, Z1, Z2)))))). forall V1 V2 (t(V1, V2) <- exists X Y (V1 = X and V2 = Y and exists Z Z1 (Z = X and Z1 = Y and e(Z, Z1))) or exists X Z Y (V1 = X and V2 = Z and (exists Z Z1 (Z = X and Z1 = Y and e(Z, Z1)) and exists Z1 Z2 (Z1 = Y and Z2 = Z and t(Z1, Z2))))). forall X1 X2 Y1 Y2 Z1 Z2 (less_t_t(X1, X2, Y1, Y2) and less_t_t(Y1, Y2, Z1, Z2) -> less_t_t(X1, X2, Z1, Z2)).",
+            ),
+        ] {
+            let program: asp::Program = src.parse().unwrap();
+            let components: Vec<IndexSet<fol::Predicate>> = program
+                .recursive_components()
+                .into_iter()
+                .map(|component| component.into_iter().map(fol::Predicate::from).collect())
+                .collect();
+            let left = ordered_completion(tau_star(program), &components).unwrap();
+            let right: fol::Theory = target.parse().unwrap();
+
+            assert!(
+                left == right,
+                "assertion `left == right` failed:\n left:\n{left}\n right:\n{right}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ordered_completion_comparison_names() {
+        for (src, comparisons) in [
+            (
+                "p :- q. q :- p. q :- not r. less_p_q :- less_p_q.",
+                vec![
+                    "less_p_p",
+                    "less_p_q_1",
+                    "less_q_p",
+                    "less_q_q",
+                    "less_less_p_q_less_p_q",
+                ],
+            ),
+            (
+                "a_b :- c. c :- a_b. a :- b_c. b_c :- a.",
+                vec![
+                    "less_a_b_a_b",
+                    "less_a_b_c",
+                    "less_c_a_b",
+                    "less_c_c",
+                    "less_a_a",
+                    "less_a_b_c_1",
+                    "less_b_c_a",
+                    "less_b_c_b_c",
+                ],
+            ),
+        ] {
+            let program: asp::Program = src.parse().unwrap();
+            let predicates = program.predicates();
+            let components: Vec<IndexSet<fol::Predicate>> = program
+                .recursive_components()
+                .into_iter()
+                .map(|component| component.into_iter().map(fol::Predicate::from).collect())
+                .collect();
+            let theory = ordered_completion(tau_star(program), &components).unwrap();
+
+            let mut symbols: Vec<_> = theory
+                .predicates()
+                .into_iter()
+                .filter(|p| !predicates.contains(&asp::Predicate::from(p.clone())))
+                .map(|p| p.symbol)
+                .collect();
+            let mut comparisons = comparisons;
+            symbols.sort();
+            comparisons.sort();
+            assert_eq!(symbols, comparisons);
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use {
+    crate::{
+        syntax_tree::{asp, fol},
+        translating::{
+            completion::{self, completion, CompletionError},
+            gamma::gamma,
+            natural::natural,
+            tau_star::tau_star,
+        },
+    },
+    indexmap::IndexMap,
+    std::fmt::{self, Display},
+};
+
+/// A translation that a formula went through
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Step {
+    TauStar,
+    Natural,
+    Completion,
+    Gamma,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::TauStar => write!(f, "tau*"),
+            Step::Natural => write!(f, "natural"),
+            Step::Completion => write!(f, "completion"),
+            Step::Gamma => write!(f, "gamma"),
+        }
+    }
+}
+
+/// A rule of a program or a formula of a theory that a translation started from, numbered from 1
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Source {
+    Rule {
+        number: usize,
+        rule: asp::Rule,
+    },
+    Formula {
+        number: usize,
+        formula: fol::Formula,
+    },
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Rule { number, rule } => {
+                write!(f, "rule {number}: {}", rule.to_string().trim_start())
+            }
+            Source::Formula { number, formula } => write!(f, "formula {number}: {formula}."),
+        }
+    }
+}
+
+/// The sources a formula originates from and the translations that produced it
+///
+/// The sources of a completed definition are ordered like its disjuncts.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Provenance {
+    pub sources: Vec<Source>,
+    pub steps: Vec<Step>,
+}
+
+impl Provenance {
+    fn then(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+}
+
+impl Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steps: Vec<_> = self.steps.iter().map(ToString::to_string).collect();
+        writeln!(f, "translated by {} from", steps.join(", "))?;
+        for source in &self.sources {
+            writeln!(f, "    {source}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A theory together with the provenance of each of its formulas
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TracedTheory {
+    pub theory: fol::Theory,
+    pub provenance: Vec<Provenance>,
+}
+
+impl TracedTheory {
+    /// Trace a theory that was not produced by a translation, whose formulas are their own sources
+    pub fn from_theory(theory: fol::Theory) -> Self {
+        let provenance = theory
+            .formulas
+            .iter()
+            .enumerate()
+            .map(|(i, formula)| Provenance {
+                sources: vec![Source::Formula {
+                    number: i + 1,
+                    formula: formula.clone(),
+                }],
+                steps: vec![],
+            })
+            .collect();
+        TracedTheory { theory, provenance }
+    }
+
+    /// Transform every formula, keeping its provenance
+    pub fn map(self, f: impl FnMut(fol::Formula) -> fol::Formula) -> Self {
+        TracedTheory {
+            theory: self.theory.formulas.into_iter().map(f).collect(),
+            provenance: self.provenance,
+        }
+    }
+
+    /// Replace every formula by a theory, whose formulas keep the provenance of the replaced formula
+    pub fn flat_map(self, mut f: impl FnMut(fol::Formula) -> fol::Theory) -> Self {
+        let mut formulas = Vec::new();
+        let mut provenance = Vec::new();
+        for (formula, origin) in self.theory.formulas.into_iter().zip(self.provenance) {
+            for formula in f(formula).formulas {
+                formulas.push(formula);
+                provenance.push(origin.clone());
+            }
+        }
+        TracedTheory {
+            theory: fol::Theory { formulas },
+            provenance,
+        }
+    }
+
+    /// The formulas and their provenance
+    pub fn iter(&self) -> impl Iterator<Item = (&fol::Formula, &Provenance)> {
+        self.theory.formulas.iter().zip(&self.provenance)
+    }
+}
+
+impl Display for TracedTheory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (formula, provenance) in self.iter() {
+            writeln!(f, "{formula}.")?;
+            for line in provenance.to_string().lines() {
+                writeln!(f, "% {line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn trace_program(
+    program: asp::Program,
+    step: Step,
+    translate: impl FnOnce(asp::Program) -> fol::Theory,
+) -> TracedTheory {
+    // Both translations produce exactly one formula per rule
+    let provenance = program
+        .rules
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| Provenance {
+            sources: vec![Source::Rule {
+                number: i + 1,
+                rule: rule.clone(),
+            }],
+            steps: vec![step],
+        })
+        .collect();
+    TracedTheory {
+        theory: translate(program),
+        provenance,
+    }
+}
+
+/// Apply `tau_star` and record the rule of each formula
+pub fn trace_tau_star(program: asp::Program) -> TracedTheory {
+    trace_program(program, Step::TauStar, tau_star)
+}
+
+/// Apply `natural` and record the rule of each formula
+pub fn trace_natural(program: asp::Program) -> TracedTheory {
+    trace_program(program, Step::Natural, natural)
+}
+
+/// Apply `completion` and record the sources of each disjunct of a completed definition
+pub fn trace_completion(theory: TracedTheory) -> Result<TracedTheory, CompletionError> {
+    let completed_theory = completion(theory.theory.clone())?;
+
+    // Completion puts the constraints first, followed by the definitions in the order of their first occurrence
+    let mut constraints = Vec::new();
+    let mut definitions: IndexMap<fol::AtomicFormula, Provenance> = IndexMap::new();
+    for (formula, provenance) in theory.iter() {
+        let (mut heads, _) = completion::components(fol::Theory {
+            formulas: vec![formula.clone()],
+        })?;
+        match heads.pop() {
+            Some((head, _)) => {
+                let definition = definitions.entry(head).or_default();
+                definition
+                    .sources
+                    .extend(provenance.sources.iter().cloned());
+                for step in &provenance.steps {
+                    if !definition.steps.contains(step) {
+                        definition.steps.push(*step);
+                    }
+                }
+            }
+            None => constraints.push(provenance.clone()),
+        }
+    }
+
+    let provenance: Vec<_> = constraints
+        .into_iter()
+        .chain(definitions.into_values())
+        .map(|provenance| provenance.then(Step::Completion))
+        .collect();
+    assert_eq!(provenance.len(), completed_theory.formulas.len());
+
+    Ok(TracedTheory {
+        theory: completed_theory,
+        provenance,
+    })
+}
+
+/// Apply `gamma`, which translates formula by formula
+pub fn trace_gamma(theory: TracedTheory) -> TracedTheory {
+    TracedTheory {
+        theory: gamma(theory.theory),
+        provenance: theory
+            .provenance
+            .into_iter()
+            .map(|provenance| provenance.then(Step::Gamma))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{
+            trace_completion, trace_gamma, trace_tau_star, Provenance, Source, Step, TracedTheory,
+        },
+        crate::{
+            syntax_tree::{asp, fol},
+            translating::{completion::completion, gamma::gamma, tau_star::tau_star},
+        },
+    };
+
+    fn rule(number: usize, src: &str) -> Source {
+        Source::Rule {
+            number,
+            rule: src.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_trace_completion() {
+        let program: asp::Program = "p(X) :- q(X). :- p(1). q(a). p(b).".parse().unwrap();
+        let traced = trace_completion(trace_tau_star(program.clone())).unwrap();
+
+        assert_eq!(traced.theory, completion(tau_star(program)).unwrap());
+        assert_eq!(
+            traced.provenance,
+            vec![
+                Provenance {
+                    sources: vec![rule(2, ":- p(1).")],
+                    steps: vec![Step::TauStar, Step::Completion],
+                },
+                Provenance {
+                    sources: vec![rule(1, "p(X) :- q(X)."), rule(4, "p(b).")],
+                    steps: vec![Step::TauStar, Step::Completion],
+                },
+                Provenance {
+                    sources: vec![rule(3, "q(a).")],
+                    steps: vec![Step::TauStar, Step::Completion],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trace_gamma() {
+        let theory: fol::Theory = "p -> q. not q -> r.".parse().unwrap();
+        let traced = trace_gamma(TracedTheory::from_theory(theory.clone()));
+
+        assert_eq!(traced.theory, gamma(theory.clone()));
+        assert_eq!(
+            traced.provenance[1],
+            Provenance {
+                sources: vec![Source::Formula {
+                    number: 2,
+                    formula: theory.formulas[1].clone(),
+                }],
+                steps: vec![Step::Gamma],
+            }
+        );
+    }
+
+    #[test]
+    fn test_traced_theory_format() {
+        let traced = trace_tau_star("a :- b.".parse().unwrap());
+        assert_eq!(
+            traced.to_string(),
+            "b -> a.\n% translated by tau* from\n%     rule 1: a :- b.\n"
+        );
+        let _: fol::Theory = traced.to_string().parse().unwrap();
+    }
+}
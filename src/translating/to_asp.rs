@@ -0,0 +1,793 @@
+use {
+    crate::{
+        convenience::with_warnings::WithWarnings,
+        syntax_tree::{asp, fol},
+        translating::{
+            completion::{components, ensure_matching_heads, Definitions},
+            loop_formulas::{fresh_variable, variable_names},
+        },
+    },
+    indexmap::IndexSet,
+    thiserror::Error,
+};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum UntranslatableFormula {
+    #[error(
+        "the following formula is neither a completed definition, a rule nor a constraint: {0}"
+    )]
+    UnsupportedShape(fol::Formula),
+    #[error("the body of the following formula is not a disjunction of conjunctions of literals and comparisons: {0}")]
+    UnsupportedBody(fol::Formula),
+    #[error("the following formula contains integer or symbolic variables, which have no counterpart in programs: {0}")]
+    UnsupportedTerm(fol::Formula),
+    #[error("the arguments of the head of the following completed definition are not distinct variables: {0}")]
+    InvalidHeadArguments(fol::Formula),
+    #[error("the head of the following completed definition does not match the head of an earlier definition of its predicate: {0}")]
+    HeadMismatch(fol::Formula),
+    #[error("the following formula defines a predicate that already has another definition: {0} -- a completed definition has to be the only definition of its predicate")]
+    RepeatedDefinition(fol::Formula),
+    #[error("the rule `{rule}` obtained from the following formula is unsafe, as not all of its variables occur in positive body literals: {formula}")]
+    UnsafeRule {
+        rule: asp::Rule,
+        formula: fol::Formula,
+    },
+    #[error("the predicate `{0}` occurs in rule bodies, but is not defined by any formula, so the program makes it false -- add a choice rule for it to leave it open")]
+    UndefinedPredicate(fol::Predicate),
+}
+
+enum Reason {
+    Shape,
+    Body,
+    Term,
+}
+
+/// Translate a theory in completed-definition form into a corresponding program where possible
+///
+/// Every formula is expected to be a completed definition `forall V (p(V) <-> F)`, a rule `forall X (F -> p(T))` or a constraint `forall X (F -> #false)`,
+/// where `F` is built from literals and comparisons by conjunction, disjunction and existential quantification.
+/// Completed definitions are read from right to left, and every disjunct of `F` becomes one rule, where a disjunct `G and not not p(T)`
+/// of a rule for `p(T)` becomes a choice rule. A completed definition has to be the only formula defining its predicate,
+/// and the arguments of its head have to be distinct variables.
+/// The formulas without a counterpart in programs and the unsafe rules are omitted and reported as warnings, as are the predicates that occur
+/// in bodies without being defined by any formula. Only if there are no warnings, the completion of the resulting program is equivalent to the given theory.
+pub fn to_asp(theory: fol::Theory) -> WithWarnings<asp::Program, UntranslatableFormula> {
+    let mut rules = Vec::new();
+    let mut warnings = Vec::new();
+
+    // The completed definitions so far, and the predicates defined by rules so far
+    let mut definitions = Definitions::new();
+    let mut defined_by_rules = IndexSet::new();
+
+    for formula in theory.formulas {
+        let translation = match translate_formula(&formula) {
+            Ok(translation) => translation,
+            Err(Reason::Shape) => {
+                warnings.push(UntranslatableFormula::UnsupportedShape(formula));
+                continue;
+            }
+            Err(Reason::Body) => {
+                warnings.push(UntranslatableFormula::UnsupportedBody(formula));
+                continue;
+            }
+            Err(Reason::Term) => {
+                warnings.push(UntranslatableFormula::UnsupportedTerm(formula));
+                continue;
+            }
+        };
+
+        match partial_definition(&formula) {
+            Some(partial_definition) => {
+                let definition = match components(fol::Theory {
+                    formulas: vec![partial_definition],
+                }) {
+                    Ok((definition, _)) => definition,
+                    Err(_) => {
+                        warnings.push(UntranslatableFormula::InvalidHeadArguments(formula));
+                        continue;
+                    }
+                };
+
+                let mut extended_definitions = definitions.clone();
+                extended_definitions.extend(definition.clone());
+                let predicates: IndexSet<_> = definition
+                    .keys()
+                    .flat_map(|head| head.predicates())
+                    .collect();
+
+                if definition.keys().any(|head| definitions.contains_key(head))
+                    || !defined_by_rules.is_disjoint(&predicates)
+                {
+                    warnings.push(UntranslatableFormula::RepeatedDefinition(formula));
+                    continue;
+                }
+                if ensure_matching_heads(&extended_definitions).is_err() {
+                    warnings.push(UntranslatableFormula::HeadMismatch(formula));
+                    continue;
+                }
+
+                definitions = extended_definitions;
+            }
+            None => {
+                let predicates: IndexSet<_> = translation
+                    .iter()
+                    .filter_map(|rule| rule.head.predicate())
+                    .map(fol::Predicate::from)
+                    .collect();
+
+                if definitions
+                    .keys()
+                    .any(|head| !head.predicates().is_disjoint(&predicates))
+                {
+                    warnings.push(UntranslatableFormula::RepeatedDefinition(formula));
+                    continue;
+                }
+
+                defined_by_rules.extend(predicates);
+            }
+        }
+
+        for rule in translation {
+            if is_safe(&rule) {
+                rules.push(rule);
+            } else {
+                warnings.push(UntranslatableFormula::UnsafeRule {
+                    rule,
+                    formula: formula.clone(),
+                });
+            }
+        }
+    }
+
+    // Predicates without any definition are false in all answer sets, unlike in the models of the theory
+    let program = asp::Program { rules };
+    let mut defined: IndexSet<_> = program
+        .head_predicates()
+        .into_iter()
+        .map(fol::Predicate::from)
+        .collect();
+    defined.extend(definitions.keys().flat_map(|head| head.predicates()));
+    for predicate in program.predicates() {
+        let predicate = fol::Predicate::from(predicate);
+        if !defined.contains(&predicate) {
+            warnings.push(UntranslatableFormula::UndefinedPredicate(predicate));
+        }
+    }
+
+    WithWarnings {
+        data: program,
+        warnings,
+    }
+}
+
+/// Whether every variable of a rule is bound by a positive body literal, possibly through equalities, as clingo requires
+fn is_safe(rule: &asp::Rule) -> bool {
+    let mut bound = IndexSet::new();
+    for formula in &rule.body.formulas {
+        if let asp::AtomicFormula::Literal(asp::Literal {
+            sign: asp::Sign::NoSign,
+            atom,
+        }) = formula
+        {
+            bound.extend(atom.terms.iter().filter_map(|term| match term {
+                asp::Term::Variable(variable) => Some(variable.clone()),
+                _ => None,
+            }));
+        }
+    }
+
+    // An equality `X = t` binds `X` once all variables of `t` are bound
+    loop {
+        let size = bound.len();
+        for formula in &rule.body.formulas {
+            if let asp::AtomicFormula::Comparison(asp::Comparison {
+                relation: asp::Relation::Equal,
+                lhs,
+                rhs,
+            }) = formula
+            {
+                for (variable, term) in [(lhs, rhs), (rhs, lhs)] {
+                    if let asp::Term::Variable(variable) = variable {
+                        if term.variables().is_subset(&bound) {
+                            bound.insert(variable.clone());
+                        }
+                    }
+                }
+            }
+        }
+        if bound.len() == size {
+            break;
+        }
+    }
+
+    rule.variables().is_subset(&bound)
+}
+
+/// The rule `forall V (F -> p(V))` of a completed definition `forall V (p(V) <-> F)`, as understood by the completion
+fn partial_definition(formula: &fol::Formula) -> Option<fol::Formula> {
+    let mut matrix = formula;
+    while let fol::Formula::QuantifiedFormula {
+        quantification:
+            fol::Quantification {
+                quantifier: fol::Quantifier::Forall,
+                ..
+            },
+        formula,
+    } = matrix
+    {
+        matrix = formula;
+    }
+
+    match matrix {
+        fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Equivalence,
+            lhs,
+            rhs,
+        } if matches!(
+            **lhs,
+            fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(_))
+        ) =>
+        {
+            Some(
+                fol::Formula::BinaryFormula {
+                    connective: fol::BinaryConnective::Implication,
+                    lhs: rhs.clone(),
+                    rhs: lhs.clone(),
+                }
+                .universal_closure(),
+            )
+        }
+        _ => None,
+    }
+}
+
+fn translate_formula(formula: &fol::Formula) -> Result<Vec<asp::Rule>, Reason> {
+    let mut scope = IndexSet::new();
+    let mut matrix = formula;
+    while let fol::Formula::QuantifiedFormula {
+        quantification:
+            fol::Quantification {
+                quantifier: fol::Quantifier::Forall,
+                variables,
+            },
+        formula,
+    } = matrix
+    {
+        for variable in variables {
+            if variable.sort != fol::Sort::General {
+                return Err(Reason::Term);
+            }
+            scope.insert(variable.name.clone());
+        }
+        matrix = formula;
+    }
+    scope.extend(
+        matrix
+            .free_variables()
+            .into_iter()
+            .map(|variable| variable.name),
+    );
+
+    let (head, body) = match matrix {
+        fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Equivalence,
+            lhs,
+            rhs,
+        } if matches!(
+            **lhs,
+            fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(_))
+        ) =>
+        {
+            (&**lhs, (**rhs).clone())
+        }
+        fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Implication,
+            lhs,
+            rhs,
+        } => (&**rhs, (**lhs).clone()),
+        fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::ReverseImplication,
+            lhs,
+            rhs,
+        } => (&**lhs, (**rhs).clone()),
+        fol::Formula::UnaryFormula {
+            connective: fol::UnaryConnective::Negation,
+            formula,
+        } => (
+            &fol::Formula::AtomicFormula(fol::AtomicFormula::Falsity),
+            (**formula).clone(),
+        ),
+        head => (head, fol::Formula::AtomicFormula(fol::AtomicFormula::Truth)),
+    };
+
+    let head = match head {
+        fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(atom)) => {
+            asp::Head::Basic(translate_atom(atom)?)
+        }
+        fol::Formula::AtomicFormula(fol::AtomicFormula::Falsity) => asp::Head::Falsity,
+        fol::Formula::AtomicFormula(fol::AtomicFormula::Truth) => return Ok(vec![]),
+        _ => return Err(Reason::Shape),
+    };
+
+    let mut taken = variable_names(formula);
+    let bodies = disjuncts(body, &mut scope, &mut taken)?;
+
+    Ok(bodies
+        .into_iter()
+        .map(|body| simplify(head.clone(), body))
+        .collect())
+}
+
+/// Bring a formula into disjunctive normal form, where existentially quantified variables become free variables
+fn disjuncts(
+    formula: fol::Formula,
+    scope: &mut IndexSet<String>,
+    taken: &mut IndexSet<String>,
+) -> Result<Vec<Vec<asp::AtomicFormula>>, Reason> {
+    match formula {
+        fol::Formula::AtomicFormula(fol::AtomicFormula::Truth) => Ok(vec![vec![]]),
+        fol::Formula::AtomicFormula(fol::AtomicFormula::Falsity) => Ok(vec![]),
+        fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(atom)) => {
+            Ok(vec![vec![literal(asp::Sign::NoSign, &atom)?]])
+        }
+        fol::Formula::AtomicFormula(fol::AtomicFormula::Comparison(comparison)) => {
+            Ok(vec![translate_comparison(&comparison, false)?])
+        }
+        fol::Formula::UnaryFormula {
+            connective: fol::UnaryConnective::Negation,
+            formula,
+        } => match *formula {
+            fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(atom)) => {
+                Ok(vec![vec![literal(asp::Sign::Negation, &atom)?]])
+            }
+            fol::Formula::AtomicFormula(fol::AtomicFormula::Comparison(comparison))
+                if comparison.guards.len() == 1 =>
+            {
+                Ok(vec![translate_comparison(&comparison, true)?])
+            }
+            fol::Formula::UnaryFormula {
+                connective: fol::UnaryConnective::Negation,
+                formula,
+            } => match *formula {
+                fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(atom)) => {
+                    Ok(vec![vec![literal(asp::Sign::DoubleNegation, &atom)?]])
+                }
+                _ => Err(Reason::Body),
+            },
+            _ => Err(Reason::Body),
+        },
+        fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Conjunction,
+            lhs,
+            rhs,
+        } => {
+            let lhs = disjuncts(*lhs, scope, taken)?;
+            let rhs = disjuncts(*rhs, scope, taken)?;
+            Ok(lhs
+                .iter()
+                .flat_map(|l| {
+                    rhs.iter()
+                        .map(|r| l.iter().chain(r.iter()).cloned().collect())
+                })
+                .collect())
+        }
+        fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Disjunction,
+            lhs,
+            rhs,
+        } => {
+            // The disjuncts become different rules, so they may reuse variable names
+            let (mut rhs_scope, mut rhs_taken) = (scope.clone(), taken.clone());
+            let mut result = disjuncts(*lhs, scope, taken)?;
+            result.extend(disjuncts(*rhs, &mut rhs_scope, &mut rhs_taken)?);
+            scope.extend(rhs_scope);
+            taken.extend(rhs_taken);
+            Ok(result)
+        }
+        fol::Formula::QuantifiedFormula {
+            quantification:
+                fol::Quantification {
+                    quantifier: fol::Quantifier::Exists,
+                    variables,
+                },
+            mut formula,
+        } => {
+            for variable in variables {
+                if variable.sort != fol::Sort::General {
+                    return Err(Reason::Term);
+                }
+                if scope.contains(&variable.name) {
+                    let fresh = fresh_variable(&variable.name, taken);
+                    formula = formula
+                        .substitute(variable, fol::GeneralTerm::Variable(fresh.clone()))
+                        .into();
+                    scope.insert(fresh);
+                } else {
+                    scope.insert(variable.name);
+                }
+            }
+            disjuncts(*formula, scope, taken)
+        }
+        _ => Err(Reason::Body),
+    }
+}
+
+/// Eliminate equalities `X = t` by substituting `t` for `X` and turn `not not p(T)` in the body of a rule for `p(T)` into a choice
+fn simplify(mut head: asp::Head, mut body: Vec<asp::AtomicFormula>) -> asp::Rule {
+    while let Some((index, variable, term)) =
+        body.iter()
+            .enumerate()
+            .find_map(|(index, formula)| match formula {
+                asp::AtomicFormula::Comparison(asp::Comparison {
+                    relation: asp::Relation::Equal,
+                    lhs,
+                    rhs,
+                }) => match (lhs, rhs) {
+                    (asp::Term::Variable(variable), term)
+                    | (term, asp::Term::Variable(variable))
+                        if !term.variables().contains(variable) =>
+                    {
+                        Some((index, variable.clone(), term.clone()))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+    {
+        body.remove(index);
+        head = match head {
+            asp::Head::Basic(atom) => asp::Head::Basic(substitute_atom(atom, &variable, &term)),
+            head => head,
+        };
+        body = body
+            .into_iter()
+            .map(|formula| match formula {
+                asp::AtomicFormula::Literal(literal) => asp::AtomicFormula::Literal(asp::Literal {
+                    sign: literal.sign,
+                    atom: substitute_atom(literal.atom, &variable, &term),
+                }),
+                asp::AtomicFormula::Comparison(comparison) => {
+                    asp::AtomicFormula::Comparison(asp::Comparison {
+                        relation: comparison.relation,
+                        lhs: substitute(comparison.lhs, &variable, &term),
+                        rhs: substitute(comparison.rhs, &variable, &term),
+                    })
+                }
+            })
+            .collect();
+    }
+
+    if let asp::Head::Basic(ref atom) = head {
+        let choice = asp::AtomicFormula::Literal(asp::Literal {
+            sign: asp::Sign::DoubleNegation,
+            atom: atom.clone(),
+        });
+        if let Some(index) = body.iter().position(|formula| *formula == choice) {
+            body.remove(index);
+            head = asp::Head::Choice(atom.clone());
+        }
+    }
+
+    asp::Rule {
+        head,
+        body: asp::Body { formulas: body },
+    }
+}
+
+fn substitute(term: asp::Term, variable: &asp::Variable, replacement: &asp::Term) -> asp::Term {
+    match term {
+        asp::Term::Variable(v) if v == *variable => replacement.clone(),
+        asp::Term::UnaryOperation { op, arg } => asp::Term::UnaryOperation {
+            op,
+            arg: substitute(*arg, variable, replacement).into(),
+        },
+        asp::Term::BinaryOperation { op, lhs, rhs } => asp::Term::BinaryOperation {
+            op,
+            lhs: substitute(*lhs, variable, replacement).into(),
+            rhs: substitute(*rhs, variable, replacement).into(),
+        },
+        term => term,
+    }
+}
+
+fn substitute_atom(
+    atom: asp::Atom,
+    variable: &asp::Variable,
+    replacement: &asp::Term,
+) -> asp::Atom {
+    asp::Atom {
+        predicate_symbol: atom.predicate_symbol,
+        terms: atom
+            .terms
+            .into_iter()
+            .map(|term| substitute(term, variable, replacement))
+            .collect(),
+    }
+}
+
+fn literal(sign: asp::Sign, atom: &fol::Atom) -> Result<asp::AtomicFormula, Reason> {
+    Ok(asp::AtomicFormula::Literal(asp::Literal {
+        sign,
+        atom: translate_atom(atom)?,
+    }))
+}
+
+fn translate_atom(atom: &fol::Atom) -> Result<asp::Atom, Reason> {
+    Ok(asp::Atom {
+        predicate_symbol: atom.predicate_symbol.clone(),
+        terms: atom
+            .terms
+            .iter()
+            .map(translate_term)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+// t0 r1 t1 r2 t2 ... becomes t0 r1 t1, t1 r2 t2, ..., where a negated comparison has a single guard
+fn translate_comparison(
+    comparison: &fol::Comparison,
+    negated: bool,
+) -> Result<Vec<asp::AtomicFormula>, Reason> {
+    let mut lhs = translate_term(&comparison.term)?;
+    let mut result = Vec::new();
+    for guard in &comparison.guards {
+        let rhs = translate_term(&guard.term)?;
+        let relation = match (&guard.relation, negated) {
+            (fol::Relation::Equal, false) | (fol::Relation::NotEqual, true) => asp::Relation::Equal,
+            (fol::Relation::NotEqual, false) | (fol::Relation::Equal, true) => {
+                asp::Relation::NotEqual
+            }
+            (fol::Relation::Less, false) | (fol::Relation::GreaterEqual, true) => {
+                asp::Relation::Less
+            }
+            (fol::Relation::LessEqual, false) | (fol::Relation::Greater, true) => {
+                asp::Relation::LessEqual
+            }
+            (fol::Relation::Greater, false) | (fol::Relation::LessEqual, true) => {
+                asp::Relation::Greater
+            }
+            (fol::Relation::GreaterEqual, false) | (fol::Relation::Less, true) => {
+                asp::Relation::GreaterEqual
+            }
+        };
+        result.push(asp::AtomicFormula::Comparison(asp::Comparison {
+            relation,
+            lhs,
+            rhs: rhs.clone(),
+        }));
+        lhs = rhs;
+    }
+    Ok(result)
+}
+
+fn translate_term(term: &fol::GeneralTerm) -> Result<asp::Term, Reason> {
+    match term {
+        fol::GeneralTerm::Infimum => Ok(asp::Term::PrecomputedTerm(asp::PrecomputedTerm::Infimum)),
+        fol::GeneralTerm::Supremum => {
+            Ok(asp::Term::PrecomputedTerm(asp::PrecomputedTerm::Supremum))
+        }
+        fol::GeneralTerm::FunctionConstant(name) => Ok(symbol(name)),
+        fol::GeneralTerm::Variable(name) => Ok(asp::Term::Variable(asp::Variable(name.clone()))),
+        fol::GeneralTerm::IntegerTerm(term) => translate_integer_term(term),
+        fol::GeneralTerm::SymbolicTerm(fol::SymbolicTerm::Symbol(name))
+        | fol::GeneralTerm::SymbolicTerm(fol::SymbolicTerm::FunctionConstant(name)) => {
+            Ok(symbol(name))
+        }
+        fol::GeneralTerm::SymbolicTerm(fol::SymbolicTerm::Variable(_)) => Err(Reason::Term),
+    }
+}
+
+fn translate_integer_term(term: &fol::IntegerTerm) -> Result<asp::Term, Reason> {
+    match term {
+        fol::IntegerTerm::Numeral(n) => Ok(asp::Term::PrecomputedTerm(
+            asp::PrecomputedTerm::Numeral(*n),
+        )),
+        fol::IntegerTerm::FunctionConstant(name) => Ok(symbol(name)),
+        fol::IntegerTerm::Variable(_) => Err(Reason::Term),
+        fol::IntegerTerm::UnaryOperation {
+            op: fol::UnaryOperator::Negative,
+            arg,
+        } => Ok(asp::Term::UnaryOperation {
+            op: asp::UnaryOperator::Negative,
+            arg: translate_integer_term(arg)?.into(),
+        }),
+        fol::IntegerTerm::BinaryOperation { op, lhs, rhs } => Ok(asp::Term::BinaryOperation {
+            op: match op {
+                fol::BinaryOperator::Add => asp::BinaryOperator::Add,
+                fol::BinaryOperator::Subtract => asp::BinaryOperator::Subtract,
+                fol::BinaryOperator::Multiply => asp::BinaryOperator::Multiply,
+            },
+            lhs: translate_integer_term(lhs)?.into(),
+            rhs: translate_integer_term(rhs)?.into(),
+        }),
+    }
+}
+
+// Placeholders become symbolic constants again
+fn symbol(name: &str) -> asp::Term {
+    asp::Term::PrecomputedTerm(asp::PrecomputedTerm::Symbol(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{to_asp, UntranslatableFormula},
+        crate::{
+            syntax_tree::{asp, fol},
+            translating::{completion::completion, tau_star::tau_star},
+        },
+    };
+
+    #[test]
+    fn test_to_asp() {
+        for (src, target) in [
+            (
+                "forall V1 (p(V1) <-> q(V1) or V1 = a).",
+                "p(V1) :- q(V1). p(a).",
+            ),
+            (
+                "forall X (q(X) and not r(X) -> p(X)).",
+                "p(X) :- q(X), not r(X).",
+            ),
+            ("forall X Y (p(X, Y) -> #false).", ":- p(X, Y)."),
+            ("not exists X (p(X) and X > 1).", ":- p(X), X > 1."),
+            ("p <- not not q. p(a). q.", "p :- not not q. p(a). q."),
+            (
+                "forall V1 (p(V1) <-> exists X (V1 = X and q(X) and not not p(V1))).",
+                "{p(X)} :- q(X).",
+            ),
+            (
+                "forall X (q(X) and 1 <= X < 3 and not X = 2 -> p(X, n)).",
+                "p(X, n) :- q(X), 1 <= X, X < 3, X != 2.",
+            ),
+            (
+                "forall X (exists Y q(X, Y) and exists Y r(Y) -> p(X)).",
+                "p(X) :- q(X, Y), r(Y1).",
+            ),
+            ("#false -> p. #true -> #true.", ""),
+        ] {
+            let left = to_asp(src.parse().unwrap());
+            let right: asp::Program = target.parse().unwrap();
+
+            // The body predicates are left open by most of these theories
+            assert!(left
+                .warnings
+                .iter()
+                .all(|warning| matches!(warning, UntranslatableFormula::UndefinedPredicate(_))));
+            assert!(
+                left.data == right,
+                "assertion `left == right` failed:\n left:\n{}\n right:\n{right}",
+                left.data
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_asp_warnings() {
+        let [shape, body, quantifier, term]: [fol::Formula; 4] = [
+            "forall X (p(X) -> q(X) or r(X))",
+            "forall X (q(X) -> p(X)) -> r",
+            "forall X (forall Y q(X, Y) -> p(X))",
+            "forall N$i (q(N$i) -> p(N$i + 1))",
+        ]
+        .map(|src| src.parse().unwrap());
+
+        let result = to_asp(fol::Theory {
+            formulas: vec![
+                shape.clone(),
+                body.clone(),
+                quantifier.clone(),
+                term.clone(),
+            ],
+        });
+        assert!(result.data.rules.is_empty());
+        assert_eq!(
+            result.warnings,
+            vec![
+                UntranslatableFormula::UnsupportedShape(shape),
+                UntranslatableFormula::UnsupportedBody(body),
+                UntranslatableFormula::UnsupportedBody(quantifier),
+                UntranslatableFormula::UnsupportedTerm(term),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_asp_definitions() {
+        let formulas: Vec<fol::Formula> = [
+            "p <-> q",
+            "p <-> r",
+            "forall X (s(X, X) <-> t(X))",
+            "forall X (u(X) <-> q(X))",
+            "forall Y (u(Y) <-> r(Y))",
+            "forall X (q(X) -> v(X))",
+            "forall X (v(X) <-> r(X))",
+            "w <-> q",
+            "r -> w",
+        ]
+        .map(|src| src.parse().unwrap())
+        .into();
+
+        let result = to_asp(fol::Theory {
+            formulas: formulas.clone(),
+        });
+        assert_eq!(
+            result.data,
+            "p :- q. u(X) :- q(X). v(X) :- q(X). w :- q."
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            result.warnings,
+            vec![
+                UntranslatableFormula::RepeatedDefinition(formulas[1].clone()),
+                UntranslatableFormula::InvalidHeadArguments(formulas[2].clone()),
+                UntranslatableFormula::HeadMismatch(formulas[4].clone()),
+                UntranslatableFormula::RepeatedDefinition(formulas[6].clone()),
+                UntranslatableFormula::RepeatedDefinition(formulas[8].clone()),
+                UntranslatableFormula::UndefinedPredicate("q/0".parse().unwrap()),
+                UntranslatableFormula::UndefinedPredicate("q/1".parse().unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_asp_open_predicates() {
+        let formulas: Vec<fol::Formula> = [
+            "forall V (p(V) <-> not q(V))",
+            "p <-> r",
+            "forall X Y (s(X) and Y = X -> t(X, Y))",
+            "forall X (t(X, X) -> s(X))",
+        ]
+        .map(|src| src.parse().unwrap())
+        .into();
+
+        let result = to_asp(fol::Theory {
+            formulas: formulas.clone(),
+        });
+        assert_eq!(
+            result.data,
+            "p :- r. t(X, X) :- s(X). s(X) :- t(X, X).".parse().unwrap()
+        );
+        assert_eq!(
+            result.warnings,
+            vec![
+                UntranslatableFormula::UnsafeRule {
+                    rule: "p(V) :- not q(V).".parse().unwrap(),
+                    formula: formulas[0].clone(),
+                },
+                UntranslatableFormula::UndefinedPredicate("r/0".parse().unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_asp_completion() {
+        for (src, target) in [
+            ("p(X) :- q(X). p(a).", "p(X) :- q(X). p(a)."),
+            (
+                "t(X, Y) :- e(X, Y). t(X, Z) :- e(X, Y), t(Y, Z).",
+                "t(X, Y) :- e(X, Y). t(X, Z) :- e(X, Y), t(Y, Z).",
+            ),
+            (
+                "{q(X)} :- p(X), not r(X). :- q(X), X > 5.",
+                ":- q(X), X > 5. {q(X)} :- p(X), not r(X).",
+            ),
+        ] {
+            let theory = completion(tau_star(src.parse().unwrap())).unwrap();
+            let left = to_asp(theory);
+            let right: asp::Program = target.parse().unwrap();
+
+            // The input predicates of these programs have no rules
+            assert!(left
+                .warnings
+                .iter()
+                .all(|warning| matches!(warning, UntranslatableFormula::UndefinedPredicate(_))));
+            assert!(
+                left.data == right,
+                "assertion `left == right` failed:\n left:\n{}\n right:\n{right}",
+                left.data
+            );
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod outline;
 pub mod problem;
 pub mod prover;
+pub mod summary;
 pub mod task;
@@ -1,14 +1,29 @@
 use {
     crate::{
         command_line::arguments::Decomposition,
-        syntax_tree::fol::{Formula, FunctionConstant, Predicate, Sort, Theory},
+        formatting::fol::fof,
+        syntax_tree::fol::{self, Direction, Formula, FunctionConstant, Predicate, Sort, Theory},
+        translating::provenance::{Provenance, TracedTheory},
     },
     anyhow::{Context as _, Result},
     indexmap::IndexSet,
     itertools::Itertools,
-    std::{fmt, fs::File, io::Write as _, iter::repeat, path::Path},
+    std::{
+        fmt::{self, Write as _},
+        fs::File,
+        io::Write as _,
+        iter::repeat,
+        path::Path,
+    },
+    thiserror::Error,
 };
 
+#[derive(Error, Debug)]
+pub enum FofError {
+    #[error("the following formula of problem `{problem}` is not expressible in untyped first-order logic: {formula}")]
+    UnsupportedFormula { problem: String, formula: Formula },
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum Interpretation {
     Standard,
@@ -40,6 +55,8 @@ pub struct AnnotatedFormula {
     pub name: String,
     pub role: Role,
     pub formula: Formula,
+    /// The rules or formulas this formula was translated from, if it was produced by a translation
+    pub provenance: Option<Provenance>,
 }
 
 impl AnnotatedFormula {
@@ -60,6 +77,7 @@ impl AnnotatedFormula {
             name: self.name,
             role: self.role,
             formula: self.formula.rename_conflicting_symbols(possible_conflicts),
+            provenance: self.provenance,
         }
     }
 }
@@ -99,12 +117,14 @@ impl Problem {
                     name: "unnamed_formula".to_string(),
                     role: anf.role,
                     formula: anf.formula,
+                    provenance: anf.provenance,
                 });
             } else if anf.name.starts_with('_') {
                 self.formulas.push(AnnotatedFormula {
                     name: format!("f{}", anf.name),
                     role: anf.role,
                     formula: anf.formula,
+                    provenance: anf.provenance,
                 });
             } else {
                 self.formulas.push(anf);
@@ -123,6 +143,39 @@ impl Problem {
         self
     }
 
+    pub fn add_traced_theory<F>(mut self, theory: TracedTheory, mut annotate: F) -> Self
+    where
+        F: FnMut(usize, Formula) -> AnnotatedFormula,
+    {
+        for (i, (formula, provenance)) in theory
+            .theory
+            .formulas
+            .into_iter()
+            .zip(theory.provenance)
+            .enumerate()
+        {
+            self.formulas.push(AnnotatedFormula {
+                provenance: Some(provenance),
+                ..annotate(i, formula)
+            })
+        }
+        self
+    }
+
+    /// Add the universal closures of the formulas of a specification, proof outline or user guide
+    ///
+    /// Lemmas become conjectures, all other formulas become axioms.
+    /// Several lemmas thus become several conjectures of the same problem, none of which is an axiom for the others.
+    pub fn add_specification(self, specification: fol::Specification) -> Self {
+        self.add_annotated_formulas(specification.formulas.into_iter().map(|formula| {
+            let role = match formula.role {
+                fol::Role::Lemma | fol::Role::InductiveLemma => Role::Conjecture,
+                fol::Role::Assumption | fol::Role::Spec | fol::Role::Definition => Role::Axiom,
+            };
+            formula.universal_closure().into_problem_formula(role)
+        }))
+    }
+
     pub fn rename_conflicting_symbols(mut self) -> Self {
         let propositional_predicates =
             IndexSet::from_iter(self.predicates().into_iter().filter(|p| p.arity == 0));
@@ -144,12 +197,20 @@ impl Problem {
                 name: format!("formula_{i}_{}", f.name),
                 role: f.role,
                 formula: f.formula,
+                provenance: f.provenance,
             });
         }
         self.formulas = formulas;
         self
     }
 
+    /// The direction of the proof the problem belongs to, as recorded in the prefix of its name
+    pub fn direction(&self) -> Option<Direction> {
+        [Direction::Forward, Direction::Backward]
+            .into_iter()
+            .find(|direction| self.name.starts_with(&direction.to_string()))
+    }
+
     pub fn axioms(&self) -> Vec<AnnotatedFormula> {
         self.formulas
             .iter()
@@ -235,6 +296,35 @@ impl Problem {
             .collect_vec()
     }
 
+    /// Format the problem in untyped first-order logic, where distinct symbols denote distinct objects
+    ///
+    /// Fails if a formula of the problem is not supported by `fof::supports`.
+    pub fn to_fof(&self) -> std::result::Result<String, FofError> {
+        if let Some(formula) = self.formulas.iter().find(|f| !fof::supports(&f.formula)) {
+            return Err(FofError::UnsupportedFormula {
+                problem: self.name.clone(),
+                formula: formula.formula.clone(),
+            });
+        }
+
+        let mut result = String::new();
+
+        let mut symbols = Vec::from_iter(self.symbols());
+        symbols.sort_unstable();
+        for (i, (s, t)) in symbols.iter().tuple_combinations().enumerate() {
+            writeln!(result, "fof(symbol_distinct_{i}, axiom, {s} != {t}).").unwrap();
+        }
+
+        for formula in &self.formulas {
+            let name = &formula.name;
+            let role = &formula.role;
+            let formula = fof::Format(&formula.formula);
+            writeln!(result, "fof({name}, {role}, {formula}).").unwrap();
+        }
+
+        Ok(result)
+    }
+
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let path = path.as_ref();
         let mut file = File::create(path)
@@ -297,7 +387,8 @@ impl fmt::Display for Problem {
 #[cfg(test)]
 mod tests {
     use {
-        super::{AnnotatedFormula, Interpretation, Problem, Role},
+        super::{AnnotatedFormula, FofError, Interpretation, Problem, Role},
+        crate::syntax_tree::fol,
         std::vec,
     };
 
@@ -311,21 +402,25 @@ mod tests {
                     name: "axiom_0".into(),
                     role: Role::Axiom,
                     formula: "p(a)".parse().unwrap(),
+                    provenance: None,
                 },
                 AnnotatedFormula {
                     name: "axiom_1".into(),
                     role: Role::Axiom,
                     formula: "forall X p(X) -> q(X)".parse().unwrap(),
+                    provenance: None,
                 },
                 AnnotatedFormula {
                     name: "conjecture_0".into(),
                     role: Role::Conjecture,
                     formula: "p(a)".parse().unwrap(),
+                    provenance: None,
                 },
                 AnnotatedFormula {
                     name: "conjecture_1".into(),
                     role: Role::Conjecture,
                     formula: "q(a)".parse().unwrap(),
+                    provenance: None,
                 },
             ],
         };
@@ -341,16 +436,19 @@ mod tests {
                             name: "axiom_0".into(),
                             role: Role::Axiom,
                             formula: "p(a)".parse().unwrap(),
+                            provenance: None,
                         },
                         AnnotatedFormula {
                             name: "axiom_1".into(),
                             role: Role::Axiom,
                             formula: "forall X p(X) -> q(X)".parse().unwrap(),
+                            provenance: None,
                         },
                         AnnotatedFormula {
                             name: "conjecture_0".into(),
                             role: Role::Conjecture,
                             formula: "p(a)".parse().unwrap(),
+                            provenance: None,
                         },
                     ],
                 },
@@ -362,16 +460,19 @@ mod tests {
                             name: "axiom_0".into(),
                             role: Role::Axiom,
                             formula: "p(a)".parse().unwrap(),
+                            provenance: None,
                         },
                         AnnotatedFormula {
                             name: "axiom_1".into(),
                             role: Role::Axiom,
                             formula: "forall X p(X) -> q(X)".parse().unwrap(),
+                            provenance: None,
                         },
                         AnnotatedFormula {
                             name: "conjecture_1".into(),
                             role: Role::Conjecture,
                             formula: "q(a)".parse().unwrap(),
+                            provenance: None,
                         },
                     ],
                 }
@@ -389,16 +490,19 @@ mod tests {
                             name: "axiom_0".into(),
                             role: Role::Axiom,
                             formula: "p(a)".parse().unwrap(),
+                            provenance: None,
                         },
                         AnnotatedFormula {
                             name: "axiom_1".into(),
                             role: Role::Axiom,
                             formula: "forall X p(X) -> q(X)".parse().unwrap(),
+                            provenance: None,
                         },
                         AnnotatedFormula {
                             name: "conjecture_0".into(),
                             role: Role::Conjecture,
                             formula: "p(a)".parse().unwrap(),
+                            provenance: None,
                         },
                     ],
                 },
@@ -410,25 +514,91 @@ mod tests {
                             name: "axiom_0".into(),
                             role: Role::Axiom,
                             formula: "p(a)".parse().unwrap(),
+                            provenance: None,
                         },
                         AnnotatedFormula {
                             name: "axiom_1".into(),
                             role: Role::Axiom,
                             formula: "forall X p(X) -> q(X)".parse().unwrap(),
+                            provenance: None,
                         },
                         AnnotatedFormula {
                             name: "conjecture_0".into(),
                             role: Role::Axiom,
                             formula: "p(a)".parse().unwrap(),
+                            provenance: None,
                         },
                         AnnotatedFormula {
                             name: "conjecture_1".into(),
                             role: Role::Conjecture,
                             formula: "q(a)".parse().unwrap(),
+                            provenance: None,
                         },
                     ],
                 }
             ]
         );
     }
+
+    #[test]
+    fn test_add_specification() {
+        let specification: fol::Specification =
+            "assumption: p(X). spec(forward): q(a). lemma(backward): exists X p(X)."
+                .parse()
+                .unwrap();
+
+        assert_eq!(
+            Problem::with_name("problem").add_specification(specification),
+            Problem {
+                name: "problem".into(),
+                interpretation: Interpretation::Standard,
+                formulas: vec![
+                    AnnotatedFormula {
+                        name: "unnamed_formula".into(),
+                        role: Role::Axiom,
+                        formula: "forall X p(X)".parse().unwrap(),
+                        provenance: None,
+                    },
+                    AnnotatedFormula {
+                        name: "unnamed_formula".into(),
+                        role: Role::Axiom,
+                        formula: "q(a)".parse().unwrap(),
+                        provenance: None,
+                    },
+                    AnnotatedFormula {
+                        name: "unnamed_formula".into(),
+                        role: Role::Conjecture,
+                        formula: "exists X p(X)".parse().unwrap(),
+                        provenance: None,
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_to_fof() {
+        let specification: fol::Specification = "assumption: p(b) or p(a). lemma: exists X p(X)."
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            Problem::with_name("problem")
+                .add_specification(specification)
+                .create_unique_formula_names()
+                .to_fof()
+                .unwrap(),
+            "fof(symbol_distinct_0, axiom, a != b).\n\
+             fof(formula_0_unnamed_formula, axiom, p(b) | p(a)).\n\
+             fof(formula_1_unnamed_formula, conjecture, ?[X_g]: (p(X_g))).\n"
+        );
+
+        let specification: fol::Specification = "lemma: exists N$i p(N$i).".parse().unwrap();
+        assert!(matches!(
+            Problem::with_name("problem")
+                .add_specification(specification)
+                .to_fof(),
+            Err(FofError::UnsupportedFormula { .. })
+        ));
+    }
 }
@@ -0,0 +1,386 @@
+use {
+    crate::verifying::{
+        problem::Problem,
+        prover::{
+            derivation::Derivation,
+            model::{Countermodel, Model},
+            proof::ReadableProof,
+            Cancellation, Prover, Report, Status, StatusExtractionError,
+        },
+    },
+    indexmap::IndexSet,
+    serde::{Deserialize, Serialize},
+    sha2::{Digest, Sha256},
+    std::{
+        env,
+        fmt::{self, Display},
+        fs, io,
+        path::PathBuf,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+};
+
+/// The result of a prover run as it is stored in the cache
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// The SZS name of the status reported by the prover
+    pub status: String,
+    pub elapsed_ms: u64,
+    pub used_axioms: Option<Vec<String>>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// The number of cache lookups that succeeded or failed and the number of results stored
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStatistics {
+    pub hits: usize,
+    pub misses: usize,
+    pub stored: usize,
+}
+
+impl CacheStatistics {
+    pub fn add(&mut self, other: CacheStatistics) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.stored += other.stored;
+    }
+}
+
+impl Display for CacheStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses, {} stored",
+            self.hits, self.misses, self.stored
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+    stored: AtomicUsize,
+}
+
+/// A directory of prover results, keyed by a hash of the problem in TPTP and of the prover's identity
+///
+/// Clones of a cache share their statistics.
+#[derive(Clone, Debug)]
+pub struct ProofCache {
+    pub directory: PathBuf,
+    counters: Arc<Counters>,
+}
+
+impl ProofCache {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        ProofCache {
+            directory: directory.into(),
+            counters: Arc::default(),
+        }
+    }
+
+    /// The platform's cache directory for anthem, e.g. `~/.cache/anthem`
+    pub fn default_directory() -> Option<PathBuf> {
+        env::var_os("XDG_CACHE_HOME")
+            .filter(|directory| !directory.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .map(|directory| directory.join("anthem"))
+    }
+
+    pub fn key(problem: &Problem, identity: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(identity.as_bytes());
+        hasher.update([0]);
+        hasher.update(problem.to_string().as_bytes());
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.directory.join(format!("{key}.json"))
+    }
+
+    /// Look up an entry, treating unreadable entries as missing
+    pub fn load(&self, key: &str) -> Option<CacheEntry> {
+        let entry = fs::read_to_string(self.path(key))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok());
+
+        let counter = match entry {
+            Some(_) => &self.counters.hits,
+            None => &self.counters.misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        entry
+    }
+
+    pub fn store(&self, key: &str, entry: &CacheEntry) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+
+        // Write to a temporary file first, such that concurrent readers never see partial entries
+        let path = self.path(key);
+        let temporary = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        fs::write(&temporary, serde_json::to_string_pretty(entry)?)?;
+        fs::rename(temporary, path)?;
+
+        self.counters.stored.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn statistics(&self) -> CacheStatistics {
+        CacheStatistics {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            stored: self.counters.stored.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CachedReport<R> {
+    /// The report of an actual prover run
+    Fresh(R),
+    /// A report restored from the cache
+    Cached { problem: Problem, entry: CacheEntry },
+}
+
+impl<R: Report> Report for CachedReport<R> {
+    fn problem(&self) -> &Problem {
+        match self {
+            CachedReport::Fresh(report) => report.problem(),
+            CachedReport::Cached { problem, .. } => problem,
+        }
+    }
+
+    fn status(&self) -> Result<Status, StatusExtractionError> {
+        match self {
+            CachedReport::Fresh(report) => report.status(),
+            CachedReport::Cached { entry, .. } => Status::from_szs_name(&entry.status),
+        }
+    }
+
+    fn elapsed_time(&self) -> Duration {
+        match self {
+            CachedReport::Fresh(report) => report.elapsed_time(),
+            CachedReport::Cached { entry, .. } => Duration::from_millis(entry.elapsed_ms),
+        }
+    }
+
+    fn stdout(&self) -> String {
+        match self {
+            CachedReport::Fresh(report) => report.stdout(),
+            CachedReport::Cached { entry, .. } => entry.stdout.clone(),
+        }
+    }
+
+    fn stderr(&self) -> String {
+        match self {
+            CachedReport::Fresh(report) => report.stderr(),
+            CachedReport::Cached { entry, .. } => entry.stderr.clone(),
+        }
+    }
+
+    fn cached(&self) -> bool {
+        matches!(self, CachedReport::Cached { .. })
+    }
+
+    fn used_axioms(&self) -> Option<IndexSet<String>> {
+        match self {
+            CachedReport::Fresh(report) => report.used_axioms(),
+            CachedReport::Cached { entry, .. } => entry
+                .used_axioms
+                .clone()
+                .map(|used_axioms| used_axioms.into_iter().collect()),
+        }
+    }
+
+    fn proof(&self) -> Option<ReadableProof> {
+        match self {
+            CachedReport::Fresh(report) => report.proof(),
+            CachedReport::Cached { entry, .. } => Derivation::from_output(&entry.stdout)
+                .ok()
+                .map(ReadableProof::from),
+        }
+    }
+
+    fn countermodel(&self) -> Option<Countermodel> {
+        match self {
+            CachedReport::Fresh(report) => report.countermodel(),
+            CachedReport::Cached { entry, .. } => Model::from_output(&entry.stdout)
+                .ok()
+                .map(|model| model.countermodel()),
+        }
+    }
+}
+
+impl<R: Report> Display for CachedReport<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CachedReport::Fresh(report) => write!(f, "{report}"),
+            CachedReport::Cached { problem, entry } => {
+                writeln!(f, "--- {} ---", problem.name)?;
+                writeln!(f)?;
+                writeln!(f, "status: {} (cached)", entry.status)
+            }
+        }
+    }
+}
+
+/// A prover that caches the results settling a problem, such that unchanged problems are not proven again
+///
+/// Only proofs and disproofs are cached, as other results such as timeouts may change with more resources.
+/// Without a cache, every problem is passed on to the prover.
+#[derive(Debug, Clone)]
+pub struct CachedProver<P> {
+    pub prover: P,
+    pub cache: Option<ProofCache>,
+}
+
+impl<P: Prover> Prover for CachedProver<P> {
+    type Error = P::Error;
+    type Report = CachedReport<P::Report>;
+
+    fn instances(&self) -> usize {
+        self.prover.instances()
+    }
+
+    fn cores(&self) -> usize {
+        self.prover.cores()
+    }
+
+    fn identity(&self) -> String {
+        self.prover.identity()
+    }
+
+    fn prove_cancellable(
+        &self,
+        problem: Problem,
+        cancellation: &Cancellation,
+    ) -> Result<Self::Report, Self::Error> {
+        let Some(cache) = &self.cache else {
+            return self
+                .prover
+                .prove_cancellable(problem, cancellation)
+                .map(CachedReport::Fresh);
+        };
+
+        let key = ProofCache::key(&problem, &self.prover.identity());
+        if let Some(entry) = cache.load(&key) {
+            return Ok(CachedReport::Cached { problem, entry });
+        }
+
+        let report = self.prover.prove_cancellable(problem, cancellation)?;
+        if let Ok(status) = report.status() {
+            if status.is_success_for_conjecture() {
+                let entry = CacheEntry {
+                    status: status.to_string(),
+                    elapsed_ms: report.elapsed_time().as_millis() as u64,
+                    used_axioms: report
+                        .used_axioms()
+                        .map(|used_axioms| used_axioms.into_iter().collect()),
+                    stdout: report.stdout(),
+                    stderr: report.stderr(),
+                };
+                // A result that cannot be stored is merely proven again next time
+                let _ = cache.store(&key, &entry);
+            }
+        }
+
+        Ok(CachedReport::Fresh(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{CacheStatistics, CachedProver, ProofCache},
+        crate::verifying::{
+            problem::{AnnotatedFormula, Problem, Role},
+            prover::{testing::TestProver, Failure, Prover, Report, Status, Success},
+        },
+        std::fs,
+    };
+
+    fn problem(conjecture: &str) -> Problem {
+        Problem::with_name("problem").add_annotated_formulas([AnnotatedFormula {
+            name: "goal".into(),
+            role: Role::Conjecture,
+            formula: conjecture.parse().unwrap(),
+            provenance: None,
+        }])
+    }
+
+    #[test]
+    fn test_cached_prover() {
+        let directory =
+            std::env::temp_dir().join(format!("anthem-test-cached-prover-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+
+        for (status, stored) in [
+            (Status::Success(Success::Theorem), true),
+            (Status::Failure(Failure::TimeOut), false),
+        ] {
+            let prover = CachedProver {
+                prover: TestProver::new(status),
+                cache: Some(ProofCache::new(directory.join(status.to_string()))),
+            };
+
+            let first = prover.prove(problem("p(a) or not p(a)")).unwrap();
+            let second = prover.prove(problem("p(a) or not p(a)")).unwrap();
+            let other = prover.prove(problem("p(b) or not p(b)")).unwrap();
+
+            assert!(!first.cached());
+            assert_eq!(second.cached(), stored);
+            assert!(!other.cached());
+            assert_eq!(second.status().unwrap(), status);
+            assert_eq!(prover.prover.runs(), if stored { 2 } else { 3 });
+            assert_eq!(
+                prover.cache.as_ref().unwrap().statistics(),
+                if stored {
+                    CacheStatistics {
+                        hits: 1,
+                        misses: 2,
+                        stored: 2,
+                    }
+                } else {
+                    CacheStatistics {
+                        hits: 0,
+                        misses: 3,
+                        stored: 0,
+                    }
+                }
+            );
+        }
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_key() {
+        let key = ProofCache::key(&problem("p(a)"), "vampire --mode casc");
+        assert_eq!(key.len(), 64);
+        assert_eq!(
+            key,
+            ProofCache::key(&problem("p(a)"), "vampire --mode casc")
+        );
+        assert_ne!(
+            key,
+            ProofCache::key(&problem("p(b)"), "vampire --mode casc")
+        );
+        assert_ne!(
+            key,
+            ProofCache::key(&problem("p(a)"), "vampire --mode casc_sat")
+        );
+    }
+}
@@ -0,0 +1,441 @@
+use {
+    indexmap::IndexSet,
+    std::{fmt, str::FromStr},
+    thiserror::Error,
+};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum DerivationExtractionError {
+    #[error("the output does not contain a derivation")]
+    Missing,
+    #[error("the derivation in the output is not terminated")]
+    Unterminated,
+    #[error("the derivation contains a malformed step: `{0}`")]
+    Malformed(String),
+}
+
+/// A TPTP general term, as used in the sources and useful info of annotated formulas
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Term {
+    Atom(String),
+    Compound(String, Vec<Term>),
+    List(Vec<Term>),
+}
+
+impl FromStr for Term {
+    type Err = DerivationExtractionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let malformed = || DerivationExtractionError::Malformed(s.to_string());
+
+        if let Some(inner) = s.strip_prefix('[') {
+            let inner = inner.strip_suffix(']').ok_or_else(malformed)?;
+            Ok(Term::List(
+                split_top_level(inner)
+                    .into_iter()
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()?,
+            ))
+        } else if s.starts_with(['\'', '"']) {
+            Ok(Term::Atom(unquote(s)))
+        } else if let Some((functor, arguments)) = s.split_once('(') {
+            let arguments = arguments.strip_suffix(')').ok_or_else(malformed)?;
+            Ok(Term::Compound(
+                functor.trim().to_string(),
+                split_top_level(arguments)
+                    .into_iter()
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()?,
+            ))
+        } else if s.is_empty() {
+            Err(malformed())
+        } else {
+            Ok(Term::Atom(s.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_all(f: &mut fmt::Formatter<'_>, terms: &[Term]) -> fmt::Result {
+            for (i, term) in terms.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{term}")?;
+            }
+            Ok(())
+        }
+
+        match self {
+            Term::Atom(atom) => write!(f, "{atom}"),
+            Term::Compound(functor, arguments) => {
+                write!(f, "{functor}(")?;
+                write_all(f, arguments)?;
+                write!(f, ")")
+            }
+            Term::List(elements) => {
+                write!(f, "[")?;
+                write_all(f, elements)?;
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// Where a step of a derivation comes from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// A formula of the input problem, optionally with its name in the input
+    File {
+        file: String,
+        name: Option<String>,
+    },
+    /// A formula inferred from its parents
+    Inference {
+        rule: String,
+        parents: Vec<Source>,
+    },
+    /// A formula introduced by the prover, e.g. a definition of a new symbol
+    Introduced(String),
+    /// A reference to another step of the derivation
+    Name(String),
+    Other(Term),
+}
+
+impl From<Term> for Source {
+    fn from(term: Term) -> Self {
+        match term {
+            Term::Compound(functor, arguments) if functor == "file" && !arguments.is_empty() => {
+                let mut arguments = arguments.into_iter();
+                Source::File {
+                    file: arguments.next().unwrap().to_string(),
+                    name: arguments.next().map(|name| name.to_string()),
+                }
+            }
+            Term::Compound(functor, arguments)
+                if functor == "inference" && arguments.len() == 3 =>
+            {
+                let mut arguments = arguments.into_iter();
+                let rule = arguments.next().unwrap().to_string();
+                let parents = match arguments.nth(1).unwrap() {
+                    Term::List(parents) => parents.into_iter().map(Source::from).collect(),
+                    parent => vec![Source::from(parent)],
+                };
+                Source::Inference { rule, parents }
+            }
+            Term::Compound(functor, arguments)
+                if functor == "introduced" && !arguments.is_empty() =>
+            {
+                Source::Introduced(arguments[0].to_string())
+            }
+            Term::Atom(name) => Source::Name(name),
+            term => Source::Other(term),
+        }
+    }
+}
+
+/// An annotated formula in a derivation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub language: String,
+    pub name: String,
+    pub role: String,
+    /// The formula, still in TPTP syntax
+    pub formula: String,
+    pub source: Option<Source>,
+}
+
+impl Step {
+    /// The name of the input formula this step restates, if any
+    pub fn input_name(&self) -> Option<&str> {
+        match &self.source {
+            Some(Source::File {
+                name: Some(name), ..
+            }) => Some(name),
+            Some(Source::Inference { .. }) | Some(Source::Introduced(_)) => None,
+            _ => Some(&self.name),
+        }
+    }
+
+    /// The names of the steps this step was inferred from
+    pub fn premises(&self) -> Vec<&str> {
+        fn collect<'a>(source: &'a Source, result: &mut Vec<&'a str>) {
+            match source {
+                Source::Name(name) => result.push(name),
+                Source::Inference { parents, .. } => {
+                    for parent in parents {
+                        collect(parent, result)
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let mut result = vec![];
+        if let Some(Source::Inference { parents, .. }) = &self.source {
+            for parent in parents {
+                collect(parent, &mut result)
+            }
+        }
+        result
+    }
+
+    fn is_assumption(&self) -> bool {
+        matches!(
+            self.role.as_str(),
+            "axiom" | "hypothesis" | "definition" | "assumption" | "lemma" | "theorem"
+        )
+    }
+}
+
+impl FromStr for Step {
+    type Err = DerivationExtractionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let malformed = || DerivationExtractionError::Malformed(s.to_string());
+
+        let (language, rest) = s.split_once('(').ok_or_else(malformed)?;
+        let rest = rest
+            .trim_end()
+            .strip_suffix('.')
+            .and_then(|rest| rest.trim_end().strip_suffix(')'))
+            .ok_or_else(malformed)?;
+
+        let mut parts = split_top_level(rest).into_iter();
+        let (Some(name), Some(role), Some(formula)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(malformed());
+        };
+        let source = parts.next().map(str::parse::<Term>).transpose()?;
+
+        Ok(Step {
+            language: language.trim().to_string(),
+            name: unquote(name),
+            role: role.trim().to_string(),
+            formula: formula.trim().to_string(),
+            source: source.map(Source::from),
+        })
+    }
+}
+
+/// A derivation, e.g. a refutation, printed by a prover in TSTP syntax
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Derivation {
+    pub steps: Vec<Step>,
+}
+
+impl Derivation {
+    /// Extract the derivation a prover printed between SZS output delimiters
+    pub fn from_output(output: &str) -> Result<Self, DerivationExtractionError> {
+        output_block(
+            output,
+            &["Proof", "Refutation", "CNFRefutation", "Derivation"],
+        )?
+        .parse()
+    }
+
+    pub fn step(&self, name: &str) -> Option<&Step> {
+        self.steps.iter().find(|step| step.name == name)
+    }
+
+    /// The names of all input assumptions the derivation relies on, in order of appearance
+    pub fn used_axioms(&self) -> IndexSet<String> {
+        self.steps
+            .iter()
+            .filter(|step| step.is_assumption())
+            .filter_map(Step::input_name)
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl FromStr for Derivation {
+    type Err = DerivationExtractionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = vec![];
+        let mut current = String::new();
+        let mut scanner = Scanner::default();
+
+        for line in s.lines() {
+            if current.is_empty() && (line.trim().is_empty() || line.starts_with('%')) {
+                continue;
+            }
+
+            for c in line.chars() {
+                current.push(c);
+                if scanner.feed(c) && scanner.depth == 0 && c == '.' {
+                    steps.push(current.trim().parse()?);
+                    current.clear();
+                }
+            }
+            current.push('\n');
+            if current.trim().is_empty() {
+                current.clear();
+            }
+        }
+
+        if !current.trim().is_empty() {
+            return Err(DerivationExtractionError::Malformed(current));
+        }
+
+        Ok(Derivation { steps })
+    }
+}
+
+/// Extract the annotated formulas a prover printed between SZS output delimiters of one of the given kinds
+pub fn output_block(output: &str, kinds: &[&str]) -> Result<String, DerivationExtractionError> {
+    let mut lines = output.lines().skip_while(|line| {
+        !line
+            .strip_prefix("% SZS output start ")
+            .and_then(|rest| rest.split_whitespace().next())
+            .is_some_and(|kind| kinds.contains(&kind))
+    });
+    lines.next().ok_or(DerivationExtractionError::Missing)?;
+
+    let mut body = String::new();
+    for line in lines {
+        if line.starts_with("% SZS output end") {
+            return Ok(body);
+        }
+        body.push_str(line);
+        body.push('\n');
+    }
+
+    Err(DerivationExtractionError::Unterminated)
+}
+
+/// Tracks nesting and quoting while scanning TPTP text character by character
+#[derive(Default)]
+struct Scanner {
+    depth: usize,
+    quote: Option<char>,
+    escaped: bool,
+}
+
+impl Scanner {
+    /// Feed the next character, returns whether it is outside of quotes
+    fn feed(&mut self, c: char) -> bool {
+        match self.quote {
+            Some(_) if self.escaped => self.escaped = false,
+            Some(_) if c == '\\' => self.escaped = true,
+            Some(q) if c == q => self.quote = None,
+            Some(_) => (),
+            None => match c {
+                '\'' | '"' => self.quote = Some(c),
+                '(' | '[' => self.depth += 1,
+                ')' | ']' => self.depth = self.depth.saturating_sub(1),
+                _ => return true,
+            },
+        }
+        false
+    }
+}
+
+/// Split at commas that are neither nested nor quoted
+fn split_top_level(s: &str) -> Vec<&str> {
+    split_top_level_at(s, ',')
+}
+
+/// Split at separators that are neither nested nor quoted
+pub(crate) fn split_top_level_at(s: &str, separator: char) -> Vec<&str> {
+    let mut result = vec![];
+    let mut scanner = Scanner::default();
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        if scanner.feed(c) && scanner.depth == 0 && c == separator {
+            result.push(&s[start..i]);
+            start = i + 1;
+        }
+    }
+
+    if !s[start..].trim().is_empty() || !result.is_empty() {
+        result.push(&s[start..]);
+    }
+
+    result
+}
+
+fn unquote(s: &str) -> String {
+    let s = s.trim();
+    s.strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .map(|s| s.replace("\\'", "'").replace("\\\\", "\\"))
+        .unwrap_or_else(|| s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{Derivation, DerivationExtractionError, Source},
+        indexmap::IndexSet,
+    };
+
+    const OUTPUT: &str = "% Refutation found. Thanks to Tanya!
+% SZS status Theorem for problem
+% SZS output start Proof for problem
+tff(type_def_5, type, general: $tType).
+tff(f3,axiom,(
+  ![X0 : general] : (p(X0) <=> q(X0))),
+  file('/dev/stdin',formula_0_completed_definition_of_p_1)).
+tff(f4,axiom,(
+  q(c_a_g)),
+  file('/dev/stdin',formula_1_lemma)).
+tff(f5,conjecture,(
+  p(c_a_g)),
+  file('/dev/stdin',formula_2_goal)).
+tff(f6,negated_conjecture,(
+  ~p(c_a_g)),
+  inference(negated_conjecture,[],[f5])).
+tff(f10,plain,(
+  $false),
+  inference(subsumption_resolution,[],[f6,inference(resolution,[],[f3,f4])])).
+fof(f11,axiom,'it''s' = X, introduced(definition,[new_symbols(naming,[sP0])])).
+% SZS output end Proof for problem
+% ------------------------------
+";
+
+    #[test]
+    fn test_from_output() {
+        let derivation = Derivation::from_output(OUTPUT).unwrap();
+
+        assert_eq!(derivation.steps.len(), 7);
+        assert_eq!(derivation.steps[1].role, "axiom");
+        assert_eq!(
+            derivation.steps[1].source,
+            Some(Source::File {
+                file: "/dev/stdin".into(),
+                name: Some("formula_0_completed_definition_of_p_1".into())
+            })
+        );
+        assert_eq!(derivation.steps[5].premises(), vec!["f6", "f3", "f4"]);
+        assert_eq!(
+            derivation.used_axioms(),
+            IndexSet::<String>::from_iter([
+                "formula_0_completed_definition_of_p_1".to_string(),
+                "formula_1_lemma".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_from_output_without_derivation() {
+        assert_eq!(
+            Derivation::from_output("% SZS status GaveUp for problem"),
+            Err(DerivationExtractionError::Missing)
+        );
+        assert_eq!(
+            Derivation::from_output(
+                "% SZS output start FiniteModel for problem\ntff(a, axiom, p).\n% SZS output end FiniteModel for problem"
+            ),
+            Err(DerivationExtractionError::Missing)
+        );
+        assert_eq!(
+            Derivation::from_output("% SZS output start Proof for problem\ntff(f1,axiom,p)."),
+            Err(DerivationExtractionError::Unterminated)
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use {
+    crate::verifying::{
+        problem::{FofError, Problem},
+        prover::{
+            process::{self, Termination},
+            Cancellation, Failure, Prover, Report, Status, StatusExtractionError,
+        },
+    },
+    std::{
+        fmt::{self, Display},
+        path::PathBuf,
+        process::Command,
+        time::{Duration, Instant},
+    },
+    thiserror::Error,
+};
+
+#[derive(Error, Debug)]
+pub enum IntuitionisticProverError {
+    #[error("unable to run `{0}` as a child process")]
+    UnableToRun(String, #[source] std::io::Error),
+    #[error("unable to convert output")]
+    UnableToConvertOutput(#[source] std::string::FromUtf8Error),
+    #[error("unable to pass the problem to an intuitionistic prover")]
+    UnsupportedProblem(#[source] FofError),
+}
+
+#[derive(Debug, Clone)]
+pub struct IntuitionisticReport {
+    pub problem: Problem,
+    pub binary: PathBuf,
+    pub stdout: String,
+    pub stderr: String,
+    pub elapsed_time: Duration,
+    pub termination: Termination,
+}
+
+impl Report for IntuitionisticReport {
+    fn problem(&self) -> &Problem {
+        &self.problem
+    }
+
+    fn status(&self) -> Result<Status, StatusExtractionError> {
+        match self.termination {
+            Termination::Exited => self.stdout.parse(),
+            Termination::TimedOut => Ok(Status::Failure(Failure::TimeOut)),
+            Termination::Cancelled => Ok(Status::Failure(Failure::UserTerminated)),
+        }
+    }
+
+    fn elapsed_time(&self) -> Duration {
+        self.elapsed_time
+    }
+
+    fn stdout(&self) -> String {
+        self.stdout.clone()
+    }
+
+    fn stderr(&self) -> String {
+        self.stderr.clone()
+    }
+}
+
+impl Display for IntuitionisticReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "--- {} ---", self.problem.name)?;
+        writeln!(f)?;
+
+        writeln!(f, "invocation: {}", self.binary.display())?;
+        writeln!(f)?;
+
+        match self.status() {
+            Ok(status) => writeln!(f, "status: {status}"),
+            Err(error) => writeln!(f, "error: {error}"),
+        }
+    }
+}
+
+/// A prover for intuitionistic logic that reads a problem in untyped TPTP syntax (`fof`) from stdin and reports an SZS status
+///
+/// This is the input format of the ILTP library, which is read by ileanCoP and nanoCoP, for instance.
+#[derive(Debug, Clone)]
+pub struct IntuitionisticProver {
+    pub binary: PathBuf,
+    pub time_limit: usize,
+    /// The number of seconds the prover may exceed its time limit before it is killed
+    pub grace_period: usize,
+    pub instances: usize,
+    pub cores: usize,
+}
+
+impl Prover for IntuitionisticProver {
+    type Error = IntuitionisticProverError;
+    type Report = IntuitionisticReport;
+
+    fn instances(&self) -> usize {
+        if self.instances == 0 {
+            std::cmp::max(num_cpus::get() / self.cores(), 1)
+        } else {
+            self.instances
+        }
+    }
+
+    fn cores(&self) -> usize {
+        if self.cores == 0 {
+            num_cpus::get()
+        } else {
+            self.cores
+        }
+    }
+
+    fn identity(&self) -> String {
+        format!("intuitionistic {}", self.binary.display())
+    }
+
+    fn prove_cancellable(
+        &self,
+        problem: Problem,
+        cancellation: &Cancellation,
+    ) -> Result<Self::Report, Self::Error> {
+        let start_time = Instant::now();
+        let input = problem
+            .to_fof()
+            .map_err(IntuitionisticProverError::UnsupportedProblem)?;
+
+        // The prover is killed once its time is up, as there is no common way to pass a time limit
+        let output = process::run(
+            &mut Command::new(&self.binary),
+            input.into_bytes(),
+            Some(Duration::from_secs(
+                (self.time_limit + self.grace_period) as u64,
+            )),
+            cancellation,
+        )
+        .map_err(|e| {
+            IntuitionisticProverError::UnableToRun(self.binary.display().to_string(), e)
+        })?;
+
+        Ok(IntuitionisticReport {
+            problem,
+            binary: self.binary.clone(),
+            stdout: String::from_utf8(output.stdout)
+                .map_err(IntuitionisticProverError::UnableToConvertOutput)?,
+            stderr: String::from_utf8(output.stderr)
+                .map_err(IntuitionisticProverError::UnableToConvertOutput)?,
+            elapsed_time: start_time.elapsed(),
+            termination: output.termination,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{IntuitionisticProver, IntuitionisticProverError},
+        crate::verifying::{
+            problem::{AnnotatedFormula, Problem, Role},
+            prover::{Cancellation, Prover},
+        },
+    };
+
+    #[test]
+    fn test_unsupported_problem() {
+        let prover = IntuitionisticProver {
+            binary: "cat".into(),
+            time_limit: 1,
+            grace_period: 1,
+            instances: 1,
+            cores: 1,
+        };
+        let problem = Problem::with_name("problem").add_annotated_formulas([AnnotatedFormula {
+            name: "lemma".into(),
+            role: Role::Conjecture,
+            formula: "exists N$i p(N$i)".parse().unwrap(),
+            provenance: None,
+        }]);
+
+        assert!(matches!(
+            prover.prove_cancellable(problem, &Cancellation::new()),
+            Err(IntuitionisticProverError::UnsupportedProblem(_))
+        ));
+    }
+}
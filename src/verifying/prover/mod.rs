@@ -1,16 +1,40 @@
 use {
-    crate::verifying::problem::Problem,
+    crate::verifying::{
+        problem::Problem,
+        prover::{
+            derivation::Derivation,
+            model::{Countermodel, Model},
+            proof::ReadableProof,
+        },
+    },
+    indexmap::IndexSet,
     lazy_static::lazy_static,
     regex::Regex,
+    serde::Serialize,
     std::{
         fmt::{Debug, Display},
         str::FromStr,
-        sync::mpsc::channel,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            mpsc::channel,
+            Arc,
+        },
+        time::Duration,
     },
     thiserror::Error,
     threadpool::ThreadPool,
 };
 
+pub mod cache;
+pub mod derivation;
+pub mod intuitionistic;
+pub mod model;
+pub mod portfolio;
+pub mod process;
+pub mod proof;
+pub mod schedule;
+#[cfg(test)]
+pub mod testing;
 pub mod vampire;
 
 lazy_static! {
@@ -27,20 +51,109 @@ pub enum StatusExtractionError {
     Unknown(String),
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub enum Success {
-    Theorem,
-    CounterSatisfiable,
-    ContradictoryAxioms,
+/// Define a part of the SZS ontology as an enum
+///
+/// Every status is given with its SZS name and the statuses it directly specializes.
+macro_rules! szs_hierarchy {
+    ($(#[$meta:meta])* $name:ident { $($(#[$variant_meta:meta])* $variant:ident = $szs:literal $(: $($parent:ident),+)?;)* }) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+        }
+
+        impl $name {
+            pub const ALL: &'static [$name] = &[$($name::$variant,)*];
+
+            /// The name of the status as printed by provers
+            pub fn szs_name(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $szs,)*
+                }
+            }
+
+            /// The statuses this status directly specializes
+            pub fn parents(&self) -> &'static [$name] {
+                match self {
+                    $($name::$variant => &[$($($name::$parent),+)?],)*
+                }
+            }
+
+            /// Check whether this status is `other` or one of its specializations
+            pub fn is_a(&self, other: $name) -> bool {
+                *self == other || self.parents().iter().any(|parent| parent.is_a(other))
+            }
+        }
+    };
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub enum Failure {
-    TimeOut,
-    MemoryOut,
-    GaveUp,
-    // UserTerminated,
-    Error,
+szs_hierarchy! {
+    /// The success statuses of the SZS ontology
+    Success {
+        Success = "Success";
+        UnsatisfiabilityPreserving = "UnsatisfiabilityPreserving": Success;
+        SatisfiabilityPreserving = "SatisfiabilityPreserving": Success;
+        EquiSatisfiable = "EquiSatisfiable": UnsatisfiabilityPreserving, SatisfiabilityPreserving;
+        Satisfiable = "Satisfiable": SatisfiabilityPreserving;
+        FinitelySatisfiable = "FinitelySatisfiable": Satisfiable;
+        FinitelyUnsatisfiable = "FinitelyUnsatisfiable": Satisfiable;
+        Theorem = "Theorem": SatisfiabilityPreserving;
+        Equivalent = "Equivalent": EquiSatisfiable, Theorem, Satisfiable;
+        TautologousConclusion = "TautologousConclusion": Theorem, Satisfiable;
+        WeakerConclusion = "WeakerConclusion": Theorem, Satisfiable;
+        EquivalentTheorem = "EquivalentTheorem": Equivalent;
+        Tautology = "Tautology": TautologousConclusion, Equivalent;
+        WeakerTautologousConclusion = "WeakerTautologousConclusion": TautologousConclusion, WeakerConclusion;
+        WeakerTheorem = "WeakerTheorem": WeakerConclusion;
+        ContradictoryAxioms = "ContradictoryAxioms": Theorem;
+        SatisfiableConclusionContradictoryAxioms = "SatisfiableConclusionContradictoryAxioms": ContradictoryAxioms;
+        TautologousConclusionContradictoryAxioms = "TautologousConclusionContradictoryAxioms": SatisfiableConclusionContradictoryAxioms;
+        WeakerConclusionContradictoryAxioms = "WeakerConclusionContradictoryAxioms": SatisfiableConclusionContradictoryAxioms;
+        CounterUnsatisfiabilityPreserving = "CounterUnsatisfiabilityPreserving": Success;
+        CounterSatisfiabilityPreserving = "CounterSatisfiabilityPreserving": Success;
+        EquiCounterSatisfiable = "EquiCounterSatisfiable": CounterUnsatisfiabilityPreserving, CounterSatisfiabilityPreserving;
+        CounterSatisfiable = "CounterSatisfiable": CounterSatisfiabilityPreserving;
+        CounterTheorem = "CounterTheorem": CounterSatisfiabilityPreserving;
+        CounterEquivalent = "CounterEquivalent": EquiCounterSatisfiable, CounterTheorem, CounterSatisfiable;
+        UnsatisfiableConclusion = "UnsatisfiableConclusion": CounterTheorem, CounterSatisfiable;
+        WeakerCounterConclusion = "WeakerCounterConclusion": CounterTheorem, CounterSatisfiable;
+        EquivalentCounterTheorem = "EquivalentCounterTheorem": CounterEquivalent;
+        Unsatisfiable = "Unsatisfiable": UnsatisfiableConclusion, CounterEquivalent;
+        WeakerUnsatisfiableConclusion = "WeakerUnsatisfiableConclusion": UnsatisfiableConclusion, WeakerCounterConclusion;
+        WeakerCounterTheorem = "WeakerCounterTheorem": WeakerCounterConclusion;
+        SatisfiableCounterConclusionContradictoryAxioms = "SatisfiableCounterConclusionContradictoryAxioms": ContradictoryAxioms;
+        UnsatisfiableConclusionContradictoryAxioms = "UnsatisfiableConclusionContradictoryAxioms": SatisfiableCounterConclusionContradictoryAxioms;
+        NoConsequence = "NoConsequence": Satisfiable, CounterSatisfiable;
+    }
+}
+
+szs_hierarchy! {
+    /// The no-success statuses of the SZS ontology
+    Failure {
+        NoSuccess = "NoSuccess";
+        Open = "Open": NoSuccess;
+        Unknown = "Unknown": NoSuccess;
+        Assumed = "Assumed": Unknown;
+        Stopped = "Stopped": Unknown;
+        Error = "Error": Stopped;
+        OsError = "OSError": Error;
+        InputError = "InputError": Error;
+        UsageError = "UsageError": Error;
+        SyntaxError = "SyntaxError": InputError;
+        SemanticError = "SemanticError": InputError;
+        TypeError = "TypeError": SemanticError;
+        Forced = "Forced": Stopped;
+        UserTerminated = "User": Forced;
+        ResourceOut = "ResourceOut": Forced;
+        TimeOut = "Timeout": ResourceOut;
+        MemoryOut = "MemoryOut": ResourceOut;
+        GaveUp = "GaveUp": Stopped;
+        Incomplete = "Incomplete": GaveUp;
+        Inappropriate = "Inappropriate": GaveUp;
+        InProgress = "InProgress": Unknown;
+        NotTried = "NotTried": Unknown;
+        NotTriedYet = "NotTriedYet": NotTried;
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -49,21 +162,73 @@ pub enum Status {
     Failure(Failure),
 }
 
+impl Status {
+    /// Look up a status by its SZS name, e.g. `Theorem`
+    pub fn from_szs_name(name: &str) -> Result<Self, StatusExtractionError> {
+        if let Some(success) = Success::ALL.iter().find(|x| x.szs_name() == name) {
+            Ok(Status::Success(*success))
+        } else if let Some(failure) = Failure::ALL.iter().find(|x| x.szs_name() == name) {
+            Ok(Status::Failure(*failure))
+        } else {
+            Err(StatusExtractionError::Unknown(name.to_string()))
+        }
+    }
+
+    /// Check whether this status is `other` or one of its specializations
+    pub fn is_a(&self, other: Status) -> bool {
+        match (self, other) {
+            (Status::Success(s), Status::Success(o)) => s.is_a(o),
+            (Status::Failure(s), Status::Failure(o)) => s.is_a(o),
+            _ => false,
+        }
+    }
+
+    /// Check whether this status establishes that the conjectures follow from the axioms
+    pub fn proves_conjecture(&self) -> bool {
+        self.is_a(Status::Success(Success::Theorem))
+    }
+
+    /// Check whether this status establishes that the conjectures do not follow from the axioms
+    pub fn disproves_conjecture(&self) -> bool {
+        self.is_a(Status::Success(Success::CounterSatisfiable))
+    }
+
+    /// Check whether this status settles a problem with conjectures one way or the other
+    pub fn is_success_for_conjecture(&self) -> bool {
+        self.proves_conjecture() || self.disproves_conjecture()
+    }
+
+    /// Check whether the prover stopped because it failed rather than because it ran out of options or resources
+    pub fn is_error(&self) -> bool {
+        self.is_a(Status::Failure(Failure::Error))
+    }
+
+    /// The verdict this status implies for a problem with conjectures
+    pub fn verdict(&self) -> Verdict {
+        if self.is_a(Status::Success(Success::ContradictoryAxioms))
+            || self.is_a(Status::Success(Success::Unsatisfiable))
+        {
+            // Contradictory axioms entail anything, which hints at a mistake in the assumptions,
+            // and unsatisfiability is only meaningful for problems without conjectures
+            Verdict::Unknown
+        } else if self.proves_conjecture() {
+            Verdict::Proved
+        } else if self.disproves_conjecture() {
+            Verdict::Disproved
+        } else if self.is_error() {
+            Verdict::Error
+        } else {
+            Verdict::Unknown
+        }
+    }
+}
+
 impl Display for Status {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Status::Success(Success::Theorem) => "Theorem",
-                Status::Success(Success::CounterSatisfiable) => "CounterSatisfiable",
-                Status::Success(Success::ContradictoryAxioms) => "ContradictoryAxioms",
-                Status::Failure(Failure::TimeOut) => "Timeout",
-                Status::Failure(Failure::MemoryOut) => "MemoryOut",
-                Status::Failure(Failure::GaveUp) => "GaveUp",
-                Status::Failure(Failure::Error) => "Error",
-            }
-        )
+        match self {
+            Status::Success(success) => write!(f, "{}", success.szs_name()),
+            Status::Failure(failure) => write!(f, "{}", failure.szs_name()),
+        }
     }
 }
 
@@ -76,21 +241,107 @@ impl FromStr for Status {
             .ok_or(StatusExtractionError::Missing)?
             .extract();
 
-        match status {
-            "Theorem" => Ok(Self::Success(Success::Theorem)),
-            "CounterSatisfiable" => Ok(Self::Success(Success::CounterSatisfiable)),
-            "ContradictoryAxioms" => Ok(Self::Success(Success::ContradictoryAxioms)),
-            "Timeout" => Ok(Self::Failure(Failure::TimeOut)),
-            "MemoryOut" => Ok(Self::Failure(Failure::MemoryOut)),
-            "GaveUp" => Ok(Self::Failure(Failure::GaveUp)),
-            "Error" => Ok(Self::Failure(Failure::Error)),
-            x => Err(StatusExtractionError::Unknown(x.to_string())),
+        Status::from_szs_name(status)
+    }
+}
+
+/// The outcome of trying to prove a problem with conjectures
+///
+/// Verdicts are ordered by severity, so the verdict of several problems is the maximum of their verdicts.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    /// The conjectures were proven
+    Proved,
+    /// The prover gave up, ran out of resources or was stopped
+    Unknown,
+    /// The conjectures were shown not to follow from the axioms
+    Disproved,
+    /// The prover failed with an error
+    Error,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Proved => write!(f, "proved"),
+            Verdict::Unknown => write!(f, "unknown"),
+            Verdict::Disproved => write!(f, "disproved"),
+            Verdict::Error => write!(f, "error"),
         }
     }
 }
 
+/// A flag that allows to stop a running prover early
+///
+/// Cancelling a cancellation also cancels all of its children, but not its parent.
+#[derive(Clone, Debug, Default)]
+pub struct Cancellation {
+    flag: Arc<AtomicBool>,
+    parent: Option<Box<Cancellation>>,
+}
+
+impl Cancellation {
+    pub fn new() -> Self {
+        Cancellation::default()
+    }
+
+    pub fn child(&self) -> Self {
+        Cancellation {
+            flag: Arc::new(AtomicBool::new(false)),
+            parent: Some(Box::new(self.clone())),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::SeqCst)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::SeqCst)
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.is_cancelled())
+    }
+}
+
 pub trait Report: Display + Debug + Clone {
+    fn problem(&self) -> &Problem;
+
     fn status(&self) -> Result<Status, StatusExtractionError>;
+
+    fn elapsed_time(&self) -> Duration;
+
+    fn stdout(&self) -> String;
+
+    fn stderr(&self) -> String;
+
+    /// Whether the report was taken from a cache instead of running the prover
+    fn cached(&self) -> bool {
+        false
+    }
+
+    /// The names of the axioms used in the derivation the prover printed, if it printed one
+    fn used_axioms(&self) -> Option<IndexSet<String>> {
+        Derivation::from_output(&self.stdout())
+            .ok()
+            .map(|derivation| derivation.used_axioms())
+    }
+
+    /// The proof the prover printed, translated back into anthem's syntax, if it printed one
+    fn proof(&self) -> Option<ReadableProof> {
+        Derivation::from_output(&self.stdout())
+            .ok()
+            .map(ReadableProof::from)
+    }
+
+    /// The countermodel the prover printed, if it printed one
+    fn countermodel(&self) -> Option<Countermodel> {
+        Model::from_output(&self.stdout())
+            .ok()
+            .map(|model| model.countermodel())
+    }
 }
 
 pub trait Prover: Debug + Clone + Send + 'static {
@@ -101,19 +352,45 @@ pub trait Prover: Debug + Clone + Send + 'static {
 
     fn cores(&self) -> usize;
 
-    fn prove(&self, problem: Problem) -> Result<Self::Report, Self::Error>;
+    /// A description of the prover and all of its options that may affect its results
+    fn identity(&self) -> String;
+
+    fn prove(&self, problem: Problem) -> Result<Self::Report, Self::Error> {
+        self.prove_cancellable(problem, &Cancellation::new())
+    }
+
+    /// Prove a problem, but give up as soon as the given cancellation is cancelled
+    fn prove_cancellable(
+        &self,
+        problem: Problem,
+        cancellation: &Cancellation,
+    ) -> Result<Self::Report, Self::Error>;
 
     fn prove_all(
         &self,
         problems: impl IntoIterator<Item = Problem> + 'static,
     ) -> Box<dyn Iterator<Item = Result<Self::Report, Self::Error>>> {
+        Box::new(
+            self.prove_all_cancellable(problems, Cancellation::new())
+                .map(|(_, result)| result),
+        )
+    }
+
+    /// Prove all problems, but give up on all of them as soon as the given cancellation is cancelled
+    ///
+    /// Every result is accompanied by its problem, such that errors can be attributed to problems.
+    #[allow(clippy::type_complexity)]
+    fn prove_all_cancellable(
+        &self,
+        problems: impl IntoIterator<Item = Problem> + 'static,
+        cancellation: Cancellation,
+    ) -> Box<dyn Iterator<Item = (Problem, Result<Self::Report, Self::Error>)>> {
         if self.instances() == 1 {
             let prover = self.clone();
-            Box::new(
-                problems
-                    .into_iter()
-                    .map(move |problem| prover.prove(problem)),
-            )
+            Box::new(problems.into_iter().map(move |problem| {
+                let result = prover.prove_cancellable(problem.clone(), &cancellation);
+                (problem, result)
+            }))
         } else {
             let pool = ThreadPool::new(self.instances());
             let (tx, rx) = channel();
@@ -121,10 +398,11 @@ pub trait Prover: Debug + Clone + Send + 'static {
             for problem in problems {
                 let prover = self.clone();
                 let tx = tx.clone();
+                let cancellation = cancellation.clone();
 
                 pool.execute(move || {
-                    let result = prover.prove(problem);
-                    tx.send(result).unwrap();
+                    let result = prover.prove_cancellable(problem.clone(), &cancellation);
+                    tx.send((problem, result)).unwrap();
                 })
             }
 
@@ -132,3 +410,109 @@ pub trait Prover: Debug + Clone + Send + 'static {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Cancellation, Failure, Status, Success, Verdict};
+
+    #[test]
+    fn test_status_from_str() {
+        for (output, status) in [
+            (
+                "% SZS status Theorem for problem",
+                Status::Success(Success::Theorem),
+            ),
+            (
+                "% SZS status Unsatisfiable for problem",
+                Status::Success(Success::Unsatisfiable),
+            ),
+            (
+                "% Refutation not found\n% SZS status Inappropriate for problem",
+                Status::Failure(Failure::Inappropriate),
+            ),
+            (
+                "% SZS status Timeout for problem",
+                Status::Failure(Failure::TimeOut),
+            ),
+            (
+                "% SZS status User for problem",
+                Status::Failure(Failure::UserTerminated),
+            ),
+        ] {
+            let parsed: Status = output.parse().unwrap();
+            assert_eq!(parsed, status);
+            assert_eq!(
+                format!("% SZS status {parsed} for problem"),
+                output.lines().last().unwrap()
+            );
+        }
+
+        assert!("% SZS status Proved for problem".parse::<Status>().is_err());
+        assert!("no status".parse::<Status>().is_err());
+    }
+
+    #[test]
+    fn test_status_hierarchy() {
+        for success in Success::ALL {
+            assert!(success.is_a(Success::Success));
+        }
+        for failure in Failure::ALL {
+            assert!(failure.is_a(Failure::NoSuccess));
+        }
+
+        assert!(Success::Tautology.is_a(Success::Theorem));
+        assert!(Success::NoConsequence.is_a(Success::CounterSatisfiable));
+        assert!(!Success::CounterSatisfiable.is_a(Success::Theorem));
+        assert!(Success::FinitelyUnsatisfiable.is_a(Success::Satisfiable));
+        assert!(!Success::FinitelyUnsatisfiable.is_a(Success::CounterSatisfiable));
+        assert!(Failure::TimeOut.is_a(Failure::ResourceOut));
+        assert!(Failure::TypeError.is_a(Failure::InputError));
+        assert!(!Failure::GaveUp.is_a(Failure::Error));
+    }
+
+    #[test]
+    fn test_verdict() {
+        for (status, verdict) in [
+            (Status::Success(Success::Theorem), Verdict::Proved),
+            (Status::Success(Success::EquivalentTheorem), Verdict::Proved),
+            (
+                Status::Success(Success::ContradictoryAxioms),
+                Verdict::Unknown,
+            ),
+            (
+                Status::Success(Success::CounterSatisfiable),
+                Verdict::Disproved,
+            ),
+            (Status::Success(Success::Unsatisfiable), Verdict::Unknown),
+            (Status::Success(Success::NoConsequence), Verdict::Disproved),
+            (Status::Success(Success::Satisfiable), Verdict::Unknown),
+            (
+                Status::Success(Success::FinitelyUnsatisfiable),
+                Verdict::Unknown,
+            ),
+            (Status::Failure(Failure::GaveUp), Verdict::Unknown),
+            (Status::Failure(Failure::MemoryOut), Verdict::Unknown),
+            (Status::Failure(Failure::SyntaxError), Verdict::Error),
+        ] {
+            assert_eq!(status.verdict(), verdict, "{status}");
+        }
+    }
+
+    #[test]
+    fn test_cancellation() {
+        let parent = Cancellation::new();
+        let child = parent.child();
+        let sibling = parent.child();
+
+        assert!(!parent.is_cancelled());
+        assert!(!child.is_cancelled());
+
+        child.cancel();
+        assert!(child.is_cancelled());
+        assert!(!sibling.is_cancelled());
+        assert!(!parent.is_cancelled());
+
+        parent.cancel();
+        assert!(sibling.is_cancelled());
+    }
+}
@@ -0,0 +1,284 @@
+use {
+    crate::{
+        syntax_tree::fol::Sort,
+        verifying::prover::derivation::{
+            output_block, split_top_level_at, Derivation, DerivationExtractionError, Term,
+        },
+    },
+    indexmap::{IndexMap, IndexSet},
+    itertools::Itertools as _,
+    std::fmt,
+};
+
+/// A finite model printed by a prover, e.g. by vampire's finite model builder
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Model {
+    /// The elements of the domain
+    pub elements: IndexSet<String>,
+    /// The elements denoted by constants and by functions applied to elements
+    pub values: IndexMap<Term, String>,
+    /// The truth values of atoms over elements
+    pub atoms: IndexMap<Term, bool>,
+}
+
+impl Model {
+    /// Extract the model a prover printed between SZS output delimiters
+    pub fn from_output(output: &str) -> Result<Self, DerivationExtractionError> {
+        let block = output_block(output, &["FiniteModel", "Model"])?;
+        let annotated_formulas: Derivation = block.parse()?;
+
+        let mut model = Model::default();
+        for step in annotated_formulas.steps {
+            match step.role.as_str() {
+                "type" => {
+                    if let Some((name, _)) = step.formula.split_once(':') {
+                        let name = name.trim();
+                        if name.starts_with("fmb_") {
+                            model.elements.insert(name.to_string());
+                        }
+                    }
+                }
+                _ if step.name == "finite_domain" || step.name == "distinct_domain" => (),
+                _ => {
+                    for literal in split_top_level_at(strip_parentheses(&step.formula), '&') {
+                        model.add_literal(strip_parentheses(literal))?
+                    }
+                }
+            }
+        }
+
+        Ok(model)
+    }
+
+    fn add_literal(&mut self, literal: &str) -> Result<(), DerivationExtractionError> {
+        if let Some(atom) = literal.strip_prefix('~') {
+            self.atoms.insert(strip_parentheses(atom).parse()?, false);
+            return Ok(());
+        }
+
+        match split_top_level_at(literal, '=').as_slice() {
+            [lhs, rhs] if !lhs.ends_with('!') => {
+                self.values.insert(lhs.parse()?, rhs.trim().to_string());
+            }
+            [atom] if !matches!(atom.trim(), "$true" | "$false") => {
+                self.atoms.insert(atom.parse()?, true);
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Undo the TPTP encoding of anthem to obtain an interpretation of placeholders and predicates
+    pub fn countermodel(&self) -> Countermodel {
+        // Name elements after the anthem terms they interpret where possible
+        let mut names: IndexMap<&str, String> = IndexMap::new();
+        for (term, element) in &self.values {
+            let name = match term {
+                Term::Atom(constant) if constant == "c__infimum__" => "#inf".to_string(),
+                Term::Atom(constant) if constant == "c__supremum__" => "#sup".to_string(),
+                Term::Compound(function, arguments)
+                    if function == "f__integer__" || function == "f__symbolic__" =>
+                {
+                    match arguments.as_slice() {
+                        [Term::Atom(value)] if !self.elements.contains(value) => value.clone(),
+                        _ => continue,
+                    }
+                }
+                _ => continue,
+            };
+            names.entry(element).or_insert(name);
+        }
+
+        let name = |term: &Term| -> String {
+            let element = match term {
+                Term::Atom(element) if self.elements.contains(element) => element.as_str(),
+                term => match self.values.get(term) {
+                    Some(element) => element.as_str(),
+                    None => return term.to_string(),
+                },
+            };
+            match names.get(element) {
+                Some(name) => name.clone(),
+                None => format!("e{}", element.rsplit('_').next().unwrap_or(element)),
+            }
+        };
+
+        let mut placeholders = vec![];
+        for term in self.values.keys() {
+            if let Term::Atom(constant) = term {
+                let sort = if constant.ends_with("_g") {
+                    Sort::General
+                } else if constant.ends_with("_i") {
+                    Sort::Integer
+                } else if constant.ends_with("_s") {
+                    Sort::Symbol
+                } else {
+                    continue;
+                };
+                placeholders.push(Placeholder {
+                    name: constant[..constant.len() - 2].to_string(),
+                    sort,
+                    value: name(term),
+                });
+            }
+        }
+
+        let mut atoms = vec![];
+        for (atom, value) in &self.atoms {
+            let (predicate, arguments) = match atom {
+                Term::Atom(predicate) => (predicate, vec![]),
+                Term::Compound(predicate, arguments) => (predicate, arguments.iter().collect()),
+                Term::List(_) => continue,
+            };
+
+            // Skip the auxiliary predicates of the standard interpretation
+            if predicate.starts_with("p__") && predicate.ends_with("__") {
+                continue;
+            }
+
+            if *value {
+                atoms.push(if arguments.is_empty() {
+                    predicate.clone()
+                } else {
+                    format!(
+                        "{predicate}({})",
+                        arguments.into_iter().map(name).format(", ")
+                    )
+                });
+            }
+        }
+
+        Countermodel {
+            domain: self
+                .elements
+                .iter()
+                .map(|e| name(&Term::Atom(e.clone())))
+                .collect(),
+            placeholders,
+            atoms,
+        }
+    }
+}
+
+fn strip_parentheses(s: &str) -> &str {
+    let mut s = s.trim();
+    while let Some(inner) = s.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+        // Only strip parentheses that enclose the whole string, as opposed to `(a) & (b)`
+        let mut depth = 0;
+        if inner.chars().any(|c| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => (),
+            }
+            depth < 0
+        }) {
+            break;
+        }
+        s = inner.trim();
+    }
+    s
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Placeholder {
+    pub name: String,
+    pub sort: Sort,
+    pub value: String,
+}
+
+/// An interpretation of the placeholders and predicates of a problem that refutes its conjectures
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Countermodel {
+    pub domain: Vec<String>,
+    pub placeholders: Vec<Placeholder>,
+    /// The atoms that are true, all other atoms are false
+    pub atoms: Vec<String>,
+}
+
+impl fmt::Display for Countermodel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "domain: {{{}}}", self.domain.iter().format(", "))?;
+
+        if !self.placeholders.is_empty() {
+            writeln!(f, "placeholders:")?;
+            for placeholder in &self.placeholders {
+                writeln!(f, "    {} = {}", placeholder.name, placeholder.value)?;
+            }
+        }
+
+        writeln!(f, "true atoms (all other atoms are false):")?;
+        for atom in &self.atoms {
+            writeln!(f, "    {atom}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{Model, Placeholder},
+        crate::syntax_tree::fol::Sort,
+    };
+
+    const OUTPUT: &str = "% SZS status CounterSatisfiable for problem
+% SZS output start FiniteModel for problem
+tff(declare_general,type,general:$tType).
+tff(declare_general1,type,fmb_general_1:general).
+tff(declare_general2,type,fmb_general_2:general).
+tff(finite_domain,axiom,
+      ! [X:general] : (
+         X = fmb_general_1 | X = fmb_general_2
+      ) ).
+
+tff(distinct_domain,axiom,
+         fmb_general_1 != fmb_general_2
+).
+
+tff(declare_n_g,type,n_g: general).
+tff(n_g_definition,axiom,n_g = fmb_general_2).
+tff(declare_c__infimum__,type,c__infimum__: general).
+tff(c__infimum___definition,axiom,c__infimum__ = fmb_general_1).
+
+tff(declare_p,type,p: general > $o ).
+tff(predicate_p,axiom,
+           ~p(fmb_general_1)
+         & p(fmb_general_2)
+
+).
+
+tff(declare_p__less__,type,p__less__: general * general > $o ).
+tff(predicate_p__less__,axiom,
+           p__less__(fmb_general_1,fmb_general_2)
+
+).
+
+tff(declare_q,type,q: $o).
+tff(q_definition,axiom,q).
+% SZS output end FiniteModel for problem
+";
+
+    #[test]
+    fn test_countermodel() {
+        let countermodel = Model::from_output(OUTPUT).unwrap().countermodel();
+
+        assert_eq!(countermodel.domain, vec!["#inf", "e2"]);
+        assert_eq!(
+            countermodel.placeholders,
+            vec![Placeholder {
+                name: "n".into(),
+                sort: Sort::General,
+                value: "e2".into(),
+            }]
+        );
+        assert_eq!(countermodel.atoms, vec!["p(e2)", "q"]);
+    }
+
+    #[test]
+    fn test_from_output_without_model() {
+        assert!(Model::from_output("% SZS status CounterSatisfiable for problem").is_err());
+    }
+}
@@ -0,0 +1,340 @@
+use {
+    crate::verifying::{
+        problem::Problem,
+        prover::{
+            model::Countermodel, proof::ReadableProof, Cancellation, Failure, Prover, Report,
+            Status, StatusExtractionError,
+        },
+    },
+    indexmap::IndexSet,
+    std::{
+        fmt::{self, Debug, Display},
+        sync::mpsc::channel,
+        thread,
+        time::{Duration, Instant},
+    },
+    thiserror::Error,
+};
+
+#[derive(Error, Debug)]
+pub struct PortfolioError<E: Debug + Display>(pub Vec<E>);
+
+impl<E: Debug + Display> Display for PortfolioError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "all members of the portfolio failed")?;
+        for (i, error) in self.0.iter().enumerate() {
+            write!(f, "\n    member {i}: {error}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum MemberOutcome<R> {
+    Finished(R),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct PortfolioReport<R> {
+    pub problem: Problem,
+    pub members: Vec<String>,
+    pub outcomes: Vec<MemberOutcome<R>>,
+    pub winner: Option<usize>,
+    pub elapsed_time: Duration,
+}
+
+impl<R: Report> PortfolioReport<R> {
+    fn finished(&self) -> impl Iterator<Item = &R> {
+        self.outcomes.iter().filter_map(|outcome| match outcome {
+            MemberOutcome::Finished(report) => Some(report),
+            MemberOutcome::Failed(_) => None,
+        })
+    }
+
+    fn combined_output(&self, output: impl Fn(&R) -> String) -> String {
+        let mut result = String::new();
+        for (i, (member, outcome)) in self.members.iter().zip(&self.outcomes).enumerate() {
+            if let MemberOutcome::Finished(report) = outcome {
+                result.push_str(&format!("% member {i} ({member})\n"));
+                result.push_str(&output(report));
+            }
+        }
+        result
+    }
+}
+
+impl<R: Report> Report for PortfolioReport<R> {
+    fn problem(&self) -> &Problem {
+        &self.problem
+    }
+
+    fn status(&self) -> Result<Status, StatusExtractionError> {
+        if let Some(MemberOutcome::Finished(report)) = self.winner.map(|i| &self.outcomes[i]) {
+            return report.status();
+        }
+
+        // Without a winner, prefer the status of a member that was not stopped by the portfolio
+        let mut fallback = Err(StatusExtractionError::Missing);
+        for report in self.finished() {
+            match report.status() {
+                Ok(Status::Failure(Failure::UserTerminated)) => {
+                    if fallback.is_err() {
+                        fallback = Ok(Status::Failure(Failure::UserTerminated))
+                    }
+                }
+                Ok(status) => return Ok(status),
+                Err(_) => (),
+            }
+        }
+        fallback
+    }
+
+    fn elapsed_time(&self) -> Duration {
+        self.elapsed_time
+    }
+
+    fn stdout(&self) -> String {
+        self.combined_output(R::stdout)
+    }
+
+    fn stderr(&self) -> String {
+        self.combined_output(R::stderr)
+    }
+
+    fn used_axioms(&self) -> Option<IndexSet<String>> {
+        match self.winner.map(|i| &self.outcomes[i]) {
+            Some(MemberOutcome::Finished(report)) => report.used_axioms(),
+            _ => None,
+        }
+    }
+
+    fn proof(&self) -> Option<ReadableProof> {
+        match self.winner.map(|i| &self.outcomes[i]) {
+            Some(MemberOutcome::Finished(report)) => report.proof(),
+            _ => None,
+        }
+    }
+
+    fn countermodel(&self) -> Option<Countermodel> {
+        match self.winner.map(|i| &self.outcomes[i]) {
+            Some(MemberOutcome::Finished(report)) => report.countermodel(),
+            _ => None,
+        }
+    }
+}
+
+impl<R: Report> Display for PortfolioReport<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "--- {} ---", self.problem.name)?;
+        writeln!(f)?;
+
+        match self.winner {
+            Some(i) => writeln!(f, "winner: member {i} ({})", self.members[i])?,
+            None => writeln!(f, "winner: none")?,
+        }
+        writeln!(f)?;
+
+        writeln!(f, "members:")?;
+        for (i, (member, outcome)) in self.members.iter().zip(&self.outcomes).enumerate() {
+            match outcome {
+                MemberOutcome::Finished(report) => match report.status() {
+                    Ok(status) => writeln!(
+                        f,
+                        "    {i}: {member} ended with status {status} after {} ms",
+                        report.elapsed_time().as_millis()
+                    )?,
+                    Err(error) => writeln!(
+                        f,
+                        "    {i}: {member} ended after {} ms with error: {error}",
+                        report.elapsed_time().as_millis()
+                    )?,
+                },
+                MemberOutcome::Failed(error) => writeln!(f, "    {i}: {member} failed: {error}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A prover that races several provers on the same problem
+///
+/// The first member that settles the problem, i.e. proves or disproves its conjectures, wins the race, all other members are cancelled.
+#[derive(Debug, Clone)]
+pub struct Portfolio<P> {
+    pub members: Vec<P>,
+    pub instances: usize,
+}
+
+impl<P> Prover for Portfolio<P>
+where
+    P: Prover + Display + Sync,
+    P::Error: Debug + Display,
+{
+    type Error = PortfolioError<P::Error>;
+    type Report = PortfolioReport<P::Report>;
+
+    fn instances(&self) -> usize {
+        if self.instances == 0 {
+            std::cmp::max(num_cpus::get() / self.cores(), 1)
+        } else {
+            self.instances
+        }
+    }
+
+    fn cores(&self) -> usize {
+        std::cmp::max(self.members.iter().map(Prover::cores).sum(), 1)
+    }
+
+    fn identity(&self) -> String {
+        let members: Vec<_> = self.members.iter().map(Prover::identity).collect();
+        format!("portfolio [{}]", members.join(", "))
+    }
+
+    fn prove_cancellable(
+        &self,
+        problem: Problem,
+        cancellation: &Cancellation,
+    ) -> Result<Self::Report, Self::Error> {
+        let start_time = Instant::now();
+        let race = cancellation.child();
+
+        let mut results: Vec<Option<Result<P::Report, P::Error>>> =
+            self.members.iter().map(|_| None).collect();
+        let mut winner = None;
+
+        thread::scope(|scope| {
+            let (tx, rx) = channel();
+
+            for (i, member) in self.members.iter().enumerate() {
+                let tx = tx.clone();
+                let problem = problem.clone();
+                let race = &race;
+                scope.spawn(move || {
+                    let result = member.prove_cancellable(problem, race);
+                    tx.send((i, result)).unwrap();
+                });
+            }
+            drop(tx);
+
+            for (i, result) in rx {
+                if winner.is_none()
+                    && result.as_ref().is_ok_and(|report| {
+                        report
+                            .status()
+                            .is_ok_and(|status| status.is_success_for_conjecture())
+                    })
+                {
+                    winner = Some(i);
+                    race.cancel();
+                }
+                results[i] = Some(result);
+            }
+        });
+
+        let results: Vec<_> = results
+            .into_iter()
+            .map(|result| result.expect("every member of the portfolio reports a result"))
+            .collect();
+
+        if !results.is_empty() && results.iter().all(Result::is_err) {
+            return Err(PortfolioError(
+                results.into_iter().filter_map(Result::err).collect(),
+            ));
+        }
+
+        Ok(PortfolioReport {
+            problem,
+            members: self.members.iter().map(ToString::to_string).collect(),
+            outcomes: results
+                .into_iter()
+                .map(|result| match result {
+                    Ok(report) => MemberOutcome::Finished(report),
+                    Err(error) => MemberOutcome::Failed(error.to_string()),
+                })
+                .collect(),
+            winner,
+            elapsed_time: start_time.elapsed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{MemberOutcome, Portfolio},
+        crate::verifying::{
+            problem::Problem,
+            prover::{
+                testing::{TestProver, TestReport},
+                Failure, Prover, Report, Status, Success,
+            },
+        },
+        std::time::Duration,
+    };
+
+    fn member_status(outcome: &MemberOutcome<TestReport>) -> Option<Status> {
+        match outcome {
+            MemberOutcome::Finished(report) => Some(report.status),
+            MemberOutcome::Failed(_) => None,
+        }
+    }
+
+    #[test]
+    fn test_portfolio() {
+        let portfolio = Portfolio {
+            members: vec![
+                TestProver {
+                    duration: Duration::from_secs(60),
+                    ..TestProver::new(Status::Success(Success::Theorem))
+                },
+                TestProver {
+                    duration: Duration::from_millis(1),
+                    ..TestProver::new(Status::Failure(Failure::GaveUp))
+                },
+                TestProver {
+                    duration: Duration::from_millis(50),
+                    ..TestProver::new(Status::Success(Success::Theorem))
+                },
+            ],
+            instances: 1,
+        };
+
+        let report = portfolio.prove(Problem::with_name("problem")).unwrap();
+
+        assert_eq!(report.winner, Some(2));
+        assert_eq!(report.status().unwrap(), Status::Success(Success::Theorem));
+        assert!(report.elapsed_time() < Duration::from_secs(60));
+        assert_eq!(
+            member_status(&report.outcomes[0]),
+            Some(Status::Failure(Failure::UserTerminated))
+        );
+        assert_eq!(
+            member_status(&report.outcomes[1]),
+            Some(Status::Failure(Failure::GaveUp))
+        );
+    }
+
+    #[test]
+    fn test_portfolio_without_winner() {
+        let portfolio = Portfolio {
+            members: vec![
+                TestProver {
+                    duration: Duration::from_millis(1),
+                    ..TestProver::new(Status::Failure(Failure::GaveUp))
+                },
+                TestProver {
+                    duration: Duration::from_millis(1),
+                    ..TestProver::new(Status::Failure(Failure::TimeOut))
+                },
+            ],
+            instances: 1,
+        };
+
+        let report = portfolio.prove(Problem::with_name("problem")).unwrap();
+
+        assert_eq!(report.winner, None);
+        assert_eq!(report.status().unwrap(), Status::Failure(Failure::GaveUp));
+    }
+}
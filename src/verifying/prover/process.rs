@@ -0,0 +1,197 @@
+use {
+    crate::verifying::prover::Cancellation,
+    std::{
+        io::{self, Read, Write},
+        process::{Child, Command, Stdio},
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// The interval in which a running process is checked for termination, cancellation or timeouts
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Termination {
+    /// The process exited on its own
+    Exited,
+    /// The process was killed by the watchdog because it exceeded its wall-clock limit
+    TimedOut,
+    /// The process was killed because its cancellation was cancelled
+    Cancelled,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProcessOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub termination: Termination,
+}
+
+/// Run a command to completion while enforcing a wall-clock limit
+///
+/// The process is started in a process group of its own, such that killing it also kills all
+/// helper processes it spawned (vampire's portfolio modes fork, for instance). The process group
+/// is killed when the process exits, when the wall-clock limit has elapsed or when the
+/// cancellation is cancelled.
+pub fn run(
+    command: &mut Command,
+    input: Vec<u8>,
+    wall_clock_limit: Option<Duration>,
+    cancellation: &Cancellation,
+) -> io::Result<ProcessOutput> {
+    let start_time = Instant::now();
+
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Feed stdin and drain stdout and stderr concurrently,
+    // otherwise a process that does not read its input or fills a pipe blocks forever
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = thread::spawn(move || stdin.write_all(&input));
+    let stdout = read_to_end(child.stdout.take().unwrap());
+    let stderr = read_to_end(child.stderr.take().unwrap());
+
+    let termination = loop {
+        if child.try_wait()?.is_some() {
+            // Helper processes that outlive the leader would keep the pipes open forever
+            kill(&mut child)?;
+            break Termination::Exited;
+        }
+
+        if cancellation.is_cancelled() {
+            kill(&mut child)?;
+            break Termination::Cancelled;
+        }
+
+        if wall_clock_limit.is_some_and(|limit| start_time.elapsed() > limit) {
+            kill(&mut child)?;
+            break Termination::TimedOut;
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    // A process may exit without consuming its entire input, which is not an error
+    match writer.join().expect("writing to stdin panicked") {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e),
+        _ => (),
+    }
+
+    Ok(ProcessOutput {
+        stdout: stdout.join().expect("reading from stdout panicked")?,
+        stderr: stderr.join().expect("reading from stderr panicked")?,
+        termination,
+    })
+}
+
+fn read_to_end(mut reader: impl Read + Send + 'static) -> thread::JoinHandle<io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        reader.read_to_end(&mut buffer)?;
+        Ok(buffer)
+    })
+}
+
+#[cfg(unix)]
+fn kill(child: &mut Child) -> io::Result<()> {
+    // The child is the leader of its process group, so its pid is also the id of the group
+    let group = -(child.id() as libc::pid_t);
+    if unsafe { libc::kill(group, libc::SIGKILL) } != 0 {
+        let error = io::Error::last_os_error();
+        // The group may already be gone if the child exited in the meantime
+        if error.raw_os_error() != Some(libc::ESRCH) {
+            return Err(error);
+        }
+    }
+    child.wait().map(|_| ())
+}
+
+#[cfg(not(unix))]
+fn kill(child: &mut Child) -> io::Result<()> {
+    child.kill()?;
+    child.wait().map(|_| ())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use {
+        super::{run, Termination},
+        crate::verifying::prover::Cancellation,
+        std::{
+            process::Command,
+            time::{Duration, Instant},
+        },
+    };
+
+    #[test]
+    fn test_exited() {
+        let output = run(
+            Command::new("cat").arg("-"),
+            b"hello".to_vec(),
+            Some(Duration::from_secs(60)),
+            &Cancellation::new(),
+        )
+        .unwrap();
+
+        assert_eq!(output.termination, Termination::Exited);
+        assert_eq!(output.stdout, b"hello");
+    }
+
+    #[test]
+    fn test_exited_with_helper() {
+        let start_time = Instant::now();
+
+        // The grandchild keeps stdout open after the leader exited
+        let output = run(
+            Command::new("sh").args(["-c", "sleep 60 & echo done"]),
+            vec![],
+            Some(Duration::from_secs(60)),
+            &Cancellation::new(),
+        )
+        .unwrap();
+
+        assert_eq!(output.termination, Termination::Exited);
+        assert_eq!(output.stdout, b"done\n");
+        assert!(start_time.elapsed() < Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_timed_out() {
+        let start_time = Instant::now();
+
+        // The grandchild keeps stdout open, so this only terminates if the whole group is killed
+        let output = run(
+            Command::new("sh").args(["-c", "sleep 60 & sleep 60"]),
+            vec![],
+            Some(Duration::from_millis(100)),
+            &Cancellation::new(),
+        )
+        .unwrap();
+
+        assert_eq!(output.termination, Termination::TimedOut);
+        assert!(start_time.elapsed() < Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let cancellation = Cancellation::new();
+        cancellation.cancel();
+
+        let output = run(
+            Command::new("sh").args(["-c", "sleep 60"]),
+            vec![],
+            None,
+            &cancellation,
+        )
+        .unwrap();
+
+        assert_eq!(output.termination, Termination::Cancelled);
+    }
+}
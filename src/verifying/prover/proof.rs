@@ -0,0 +1,135 @@
+use {
+    crate::{
+        parsing::{fol::tptp::FormulaParser, Parser as _},
+        syntax_tree::fol::Formula,
+        verifying::prover::derivation::{Derivation, Source, Step},
+    },
+    indexmap::IndexMap,
+    itertools::Itertools as _,
+    lazy_static::lazy_static,
+    regex::Regex,
+    std::fmt,
+};
+
+lazy_static! {
+    static ref UNIQUE_NAME: Regex = Regex::new(r"^formula_[0-9]+_(?<name>.+)$").unwrap();
+}
+
+/// Undo `Problem::create_unique_formula_names`
+fn original_name(name: &str) -> &str {
+    match UNIQUE_NAME.captures(name) {
+        Some(captures) => captures.name("name").unwrap().as_str(),
+        None => name,
+    }
+}
+
+/// A step of a proof with its formula translated back into anthem's syntax
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadableStep {
+    pub name: String,
+    pub role: String,
+    /// The formula, or its TPTP representation if it has no counterpart in anthem's syntax
+    pub formula: Result<Formula, String>,
+    pub rule: Option<String>,
+    pub premises: Vec<String>,
+}
+
+/// A proof printed by a prover, translated back into anthem's syntax
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadableProof {
+    pub steps: Vec<ReadableStep>,
+}
+
+impl From<Derivation> for ReadableProof {
+    fn from(derivation: Derivation) -> Self {
+        // Steps restating formulas of the problem are named after them
+        let names: IndexMap<&str, &str> = derivation
+            .steps
+            .iter()
+            .map(|step| {
+                let name = match step.input_name() {
+                    Some(name) if step.role != "type" => original_name(name),
+                    _ => &step.name,
+                };
+                (step.name.as_str(), name)
+            })
+            .collect();
+
+        let steps = derivation
+            .steps
+            .iter()
+            .filter(|step| step.role != "type")
+            .map(|step: &Step| ReadableStep {
+                name: names[step.name.as_str()].to_string(),
+                role: step.role.clone(),
+                formula: FormulaParser::parse(&step.formula).map_err(|_| step.formula.clone()),
+                rule: match &step.source {
+                    Some(Source::Inference { rule, .. }) => Some(rule.clone()),
+                    _ => None,
+                },
+                premises: step
+                    .premises()
+                    .into_iter()
+                    .map(|premise| names.get(premise).unwrap_or(&premise).to_string())
+                    .collect(),
+            })
+            .collect();
+
+        ReadableProof { steps }
+    }
+}
+
+impl fmt::Display for ReadableProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            write!(f, "{} ({}): ", step.name, step.role)?;
+            match &step.formula {
+                Ok(formula) => write!(f, "{formula}.")?,
+                Err(tptp) => write!(f, "{}. (in TPTP)", tptp.split_whitespace().join(" "))?,
+            }
+            if let Some(rule) = &step.rule {
+                write!(f, " [{rule}")?;
+                if !step.premises.is_empty() {
+                    write!(f, " from {}", step.premises.iter().format(", "))?;
+                }
+                write!(f, "]")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::ReadableProof, crate::verifying::prover::derivation::Derivation};
+
+    #[test]
+    fn test_readable_proof() {
+        let derivation = Derivation::from_output(
+            "% SZS output start Proof for problem
+tff(type_def_5, type, general: $tType).
+tff(f3,axiom,(
+  ! [X0 : general] : (p(X0) <=> q(X0))),
+  file(unknown,formula_0_completed_definition_of_p_1)).
+tff(f4,axiom,q(a_g),file(unknown,formula_1_lemma)).
+tff(f5,conjecture,p(a_g),file(unknown,formula_2_goal)).
+tff(f6,negated_conjecture,~p(a_g),inference(negated_conjecture,[],[f5])).
+tff(f7,plain,p(sK0(a_g)),inference(resolution,[],[f3,f4])).
+tff(f8,plain,$false,inference(subsumption_resolution,[],[f6,f3,f4])).
+% SZS output end Proof for problem",
+        )
+        .unwrap();
+
+        assert_eq!(
+            ReadableProof::from(derivation).to_string(),
+            "completed_definition_of_p_1 (axiom): forall X0 (p(X0) <-> q(X0)).
+lemma (axiom): q(a$g).
+goal (conjecture): p(a$g).
+f6 (negated_conjecture): not p(a$g). [negated_conjecture from goal]
+f7 (plain): p(sK0(a_g)). (in TPTP) [resolution from completed_definition_of_p_1, lemma]
+f8 (plain): #false. [subsumption_resolution from f6, completed_definition_of_p_1, lemma]
+"
+        );
+    }
+}
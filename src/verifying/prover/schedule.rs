@@ -0,0 +1,284 @@
+use {
+    crate::verifying::{
+        problem::Problem,
+        prover::{Cancellation, Prover, Report, Verdict},
+    },
+    std::sync::mpsc::channel,
+    threadpool::ThreadPool,
+};
+
+/// The result of a scheduled problem
+#[derive(Debug)]
+pub enum Scheduled<R, E> {
+    Finished(Result<R, E>),
+    /// The problem was not proven, as it relies on conjectures of the named problems, which were not proven
+    Skipped(Vec<String>),
+}
+
+/// A list of problems together with the dependencies between them
+///
+/// A problem depends on an earlier problem of the same direction if it uses one of the conjectures of the earlier problem as an axiom,
+/// as under sequential decomposition, where conjectures become lemmas of later problems.
+#[derive(Clone, Debug)]
+pub struct Schedule {
+    pub problems: Vec<Problem>,
+    /// The indices of the problems each problem directly depends on
+    pub premises: Vec<Vec<usize>>,
+}
+
+impl Schedule {
+    pub fn new(problems: Vec<Problem>) -> Self {
+        let premises = problems
+            .iter()
+            .enumerate()
+            .map(|(i, problem)| {
+                let axioms: Vec<_> = problem
+                    .axioms()
+                    .into_iter()
+                    .map(|axiom| axiom.formula)
+                    .collect();
+                problems[..i]
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, earlier)| {
+                        earlier.direction() == problem.direction()
+                            && earlier
+                                .conjectures()
+                                .iter()
+                                .any(|conjecture| axioms.contains(&conjecture.formula))
+                    })
+                    .map(|(j, _)| j)
+                    .collect()
+            })
+            .collect();
+
+        Schedule { problems, premises }
+    }
+
+    /// The indices of all problems that directly or indirectly depend on the given problem
+    pub fn dependents(&self, index: usize) -> Vec<usize> {
+        let mut dependents = vec![false; self.problems.len()];
+        dependents[index] = true;
+        for i in index + 1..self.problems.len() {
+            dependents[i] = self.premises[i].iter().any(|&j| dependents[j]);
+        }
+        (index + 1..self.problems.len())
+            .filter(|&i| dependents[i])
+            .collect()
+    }
+
+    /// Prove all problems with the given prover, returning their results in the order of the problems
+    ///
+    /// Independent problems are proven concurrently by as many instances as the prover allows.
+    /// With `fail_fast`, a problem is only started once all problems it depends on are proven,
+    /// and skipped as soon as one of them is not.
+    #[allow(clippy::type_complexity)]
+    pub fn run<P: Prover>(
+        self,
+        prover: &P,
+        fail_fast: bool,
+        cancellation: Cancellation,
+    ) -> Box<dyn Iterator<Item = (Problem, Scheduled<P::Report, P::Error>)>> {
+        let pool = ThreadPool::new(prover.instances());
+        let (tx, rx) = channel();
+        let submit = {
+            let prover = prover.clone();
+            move |i: usize, problem: Problem| {
+                let prover = prover.clone();
+                let tx = tx.clone();
+                let cancellation = cancellation.clone();
+                pool.execute(move || {
+                    let result = prover.prove_cancellable(problem, &cancellation);
+                    // The receiver is gone if the caller stopped consuming results
+                    let _ = tx.send((i, result));
+                })
+            }
+        };
+
+        // Without `fail_fast`, the results of premises do not matter, so all problems start right away
+        let mut pending: Vec<usize> = self
+            .premises
+            .iter()
+            .map(|premises| if fail_fast { premises.len() } else { 0 })
+            .collect();
+        for (i, problem) in self.problems.iter().enumerate() {
+            if pending[i] == 0 {
+                submit(i, problem.clone());
+            }
+        }
+
+        let mut results: Vec<Option<Scheduled<P::Report, P::Error>>> =
+            self.problems.iter().map(|_| None).collect();
+        let mut failed_premises: Vec<Vec<String>> = self.problems.iter().map(|_| vec![]).collect();
+        let mut next = 0;
+
+        Box::new(std::iter::from_fn(move || {
+            if next == self.problems.len() {
+                return None;
+            }
+
+            // Premises precede their dependents, so the next problem was started once all earlier ones were settled
+            while results[next].is_none() {
+                let (i, result) = rx.recv().expect("every started problem reports a result");
+
+                let proven = matches!(
+                    &result,
+                    Ok(report) if report.status().is_ok_and(|status| status.verdict() == Verdict::Proved)
+                );
+                if fail_fast && !proven {
+                    for j in self.dependents(i) {
+                        failed_premises[j].push(self.problems[i].name.clone());
+                    }
+                }
+                results[i] = Some(Scheduled::Finished(result));
+
+                // Start the dependents whose premises are settled now, or skip them if one was not proven
+                let mut settled = vec![i];
+                while let Some(k) = settled.pop() {
+                    if !fail_fast {
+                        break;
+                    }
+                    for j in k + 1..self.problems.len() {
+                        if !self.premises[j].contains(&k) {
+                            continue;
+                        }
+                        pending[j] -= 1;
+                        if pending[j] > 0 {
+                            continue;
+                        }
+                        if failed_premises[j].is_empty() {
+                            submit(j, self.problems[j].clone());
+                        } else {
+                            results[j] = Some(Scheduled::Skipped(failed_premises[j].clone()));
+                            settled.push(j);
+                        }
+                    }
+                }
+            }
+
+            let i = next;
+            next += 1;
+
+            let result = results[i].take().expect("the problem was settled");
+            Some((self.problems[i].clone(), result))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{Schedule, Scheduled},
+        crate::verifying::{
+            problem::{AnnotatedFormula, Problem, Role},
+            prover::{testing::TestProver, Cancellation, Failure, Status, Success},
+        },
+        std::time::Duration,
+    };
+
+    fn formula(name: &str, role: Role, formula: &str) -> AnnotatedFormula {
+        AnnotatedFormula {
+            name: name.into(),
+            role,
+            formula: formula.parse().unwrap(),
+            provenance: None,
+        }
+    }
+
+    fn problems() -> Vec<Problem> {
+        vec![
+            Problem::with_name("a").add_annotated_formulas([
+                formula("axiom", Role::Axiom, "p"),
+                formula("unprovable", Role::Conjecture, "q"),
+            ]),
+            Problem::with_name("bb").add_annotated_formulas([
+                formula("axiom", Role::Axiom, "p"),
+                formula("other", Role::Conjecture, "p or r"),
+            ]),
+            Problem::with_name("ccc").add_annotated_formulas([
+                formula("axiom", Role::Axiom, "p"),
+                formula("unprovable", Role::Axiom, "q"),
+                formula("lemma", Role::Conjecture, "q or p"),
+            ]),
+            Problem::with_name("dddd").add_annotated_formulas([
+                formula("axiom", Role::Axiom, "p"),
+                formula("lemma", Role::Axiom, "q or p"),
+                formula("goal", Role::Conjecture, "p"),
+            ]),
+        ]
+    }
+
+    #[test]
+    fn test_premises() {
+        let schedule = Schedule::new(problems());
+        assert_eq!(
+            schedule.premises,
+            [vec![], vec![], vec![0], vec![2]] as [Vec<usize>; 4]
+        );
+        assert_eq!(schedule.dependents(0), [2, 3]);
+        assert_eq!(schedule.dependents(1), [] as [usize; 0]);
+
+        // The conjectures of one direction are the axioms of the other under strong equivalence
+        let schedule = Schedule::new(vec![
+            Problem::with_name("forward_0").add_annotated_formulas([
+                formula("left", Role::Axiom, "p"),
+                formula("right", Role::Conjecture, "q"),
+            ]),
+            Problem::with_name("backward_0").add_annotated_formulas([
+                formula("right", Role::Axiom, "q"),
+                formula("left", Role::Conjecture, "p"),
+            ]),
+        ]);
+        assert_eq!(schedule.premises, [vec![], vec![]] as [Vec<usize>; 2]);
+    }
+
+    #[test]
+    fn test_run() {
+        for fail_fast in [false, true] {
+            // Later problems finish first to check that results are returned in order
+            let prover = TestProver {
+                statuses: [("a".into(), Status::Failure(Failure::GaveUp))].into(),
+                durations: [("a", 15), ("bb", 10), ("ccc", 5), ("dddd", 0)]
+                    .map(|(name, ms)| (name.into(), Duration::from_millis(ms)))
+                    .into(),
+                instances: 3,
+                ..TestProver::new(Status::Success(Success::Theorem))
+            };
+
+            let results: Vec<_> = Schedule::new(problems())
+                .run(&prover, fail_fast, Cancellation::new())
+                .map(|(problem, result)| {
+                    let outcome = match result {
+                        Scheduled::Finished(Ok(report)) => report.status.to_string(),
+                        Scheduled::Finished(Err(error)) => match error {},
+                        Scheduled::Skipped(premises) => format!("skipped {premises:?}"),
+                    };
+                    (problem.name, outcome)
+                })
+                .collect();
+
+            let expected = if fail_fast {
+                [
+                    ("a", "GaveUp"),
+                    ("bb", "Theorem"),
+                    ("ccc", "skipped [\"a\"]"),
+                    ("dddd", "skipped [\"a\"]"),
+                ]
+            } else {
+                [
+                    ("a", "GaveUp"),
+                    ("bb", "Theorem"),
+                    ("ccc", "Theorem"),
+                    ("dddd", "Theorem"),
+                ]
+            };
+            assert_eq!(
+                results,
+                expected.map(|(name, outcome)| (name.to_string(), outcome.to_string()))
+            );
+
+            // Problems depending on unproven ones are never passed to the prover
+            assert_eq!(prover.runs(), if fail_fast { 2 } else { 4 });
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use {
+    crate::verifying::{
+        problem::Problem,
+        prover::{Cancellation, Failure, Prover, Report, Status, StatusExtractionError},
+    },
+    indexmap::IndexMap,
+    std::{
+        convert::Infallible,
+        fmt::{self, Display},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// A prover that reports a predetermined status after a predetermined time, unless it is cancelled before
+#[derive(Debug, Clone)]
+pub struct TestProver {
+    /// The status of every problem whose name is not in `statuses`
+    pub status: Status,
+    pub statuses: IndexMap<String, Status>,
+    /// The time it takes to prove every problem whose name is not in `durations`
+    pub duration: Duration,
+    pub durations: IndexMap<String, Duration>,
+    pub instances: usize,
+    /// The number of problems passed to this prover or any of its clones
+    pub runs: Arc<AtomicUsize>,
+}
+
+impl TestProver {
+    pub fn new(status: Status) -> Self {
+        TestProver {
+            status,
+            statuses: IndexMap::new(),
+            duration: Duration::ZERO,
+            durations: IndexMap::new(),
+            instances: 1,
+            runs: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn runs(&self) -> usize {
+        self.runs.load(Ordering::SeqCst)
+    }
+}
+
+impl Display for TestProver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "test ({}, {} ms)",
+            self.status,
+            self.duration.as_millis()
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TestReport {
+    pub problem: Problem,
+    pub status: Status,
+    pub elapsed_time: Duration,
+}
+
+impl Display for TestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.status)
+    }
+}
+
+impl Report for TestReport {
+    fn problem(&self) -> &Problem {
+        &self.problem
+    }
+
+    fn status(&self) -> Result<Status, StatusExtractionError> {
+        Ok(self.status)
+    }
+
+    fn elapsed_time(&self) -> Duration {
+        self.elapsed_time
+    }
+
+    fn stdout(&self) -> String {
+        format!("% SZS status {} for {}", self.status, self.problem.name)
+    }
+
+    fn stderr(&self) -> String {
+        String::new()
+    }
+}
+
+impl Prover for TestProver {
+    type Error = Infallible;
+    type Report = TestReport;
+
+    fn instances(&self) -> usize {
+        self.instances
+    }
+
+    fn cores(&self) -> usize {
+        1
+    }
+
+    fn identity(&self) -> String {
+        self.to_string()
+    }
+
+    fn prove_cancellable(
+        &self,
+        problem: Problem,
+        cancellation: &Cancellation,
+    ) -> Result<Self::Report, Self::Error> {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+
+        let start_time = Instant::now();
+        let duration = *self.durations.get(&problem.name).unwrap_or(&self.duration);
+        let mut status = *self.statuses.get(&problem.name).unwrap_or(&self.status);
+        while start_time.elapsed() < duration {
+            if cancellation.is_cancelled() {
+                status = Status::Failure(Failure::UserTerminated);
+                break;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        Ok(TestReport {
+            problem,
+            status,
+            elapsed_time: start_time.elapsed(),
+        })
+    }
+}
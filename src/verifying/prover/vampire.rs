@@ -1,12 +1,18 @@
 use {
     crate::verifying::{
         problem::Problem,
-        prover::{Prover, Report, Status, StatusExtractionError},
+        prover::{
+            process::{self, Termination},
+            Cancellation, Failure, Prover, Report, Status, StatusExtractionError,
+        },
     },
+    clap::ValueEnum,
+    itertools::Itertools as _,
     std::{
         fmt::{self, Display},
-        io::Write as _,
-        process::{Command, Output, Stdio},
+        path::PathBuf,
+        process::Command,
+        str::FromStr,
         time::{Duration, Instant},
     },
     thiserror::Error,
@@ -14,12 +20,8 @@ use {
 
 #[derive(Error, Debug)]
 pub enum VampireError {
-    #[error("unable to spawn vampire as a child process")]
-    UnableToSpawn(#[source] std::io::Error),
-    #[error("unable to write to vampire's stdin")]
-    UnableToWrite(#[source] std::io::Error),
-    #[error("unable to wait for vampire")]
-    UnableToWait(#[source] std::io::Error),
+    #[error("unable to run `{0}` as a child process")]
+    UnableToRun(String, #[source] std::io::Error),
     #[error("unable to convert output")]
     UnableToConvertOutput(#[source] std::string::FromUtf8Error),
 }
@@ -30,28 +32,65 @@ pub struct VampireOutput {
     pub stderr: String,
 }
 
-impl TryFrom<Output> for VampireOutput {
-    type Error = VampireError;
-
-    fn try_from(value: Output) -> Result<Self, Self::Error> {
+impl VampireOutput {
+    fn from_bytes(stdout: Vec<u8>, stderr: Vec<u8>) -> Result<Self, VampireError> {
         // TODO: Should we do something about the exit status?!
         Ok(VampireOutput {
-            stdout: String::from_utf8(value.stdout).map_err(VampireError::UnableToConvertOutput)?,
-            stderr: String::from_utf8(value.stderr).map_err(VampireError::UnableToConvertOutput)?,
+            stdout: String::from_utf8(stdout).map_err(VampireError::UnableToConvertOutput)?,
+            stderr: String::from_utf8(stderr).map_err(VampireError::UnableToConvertOutput)?,
         })
     }
 }
 
+/// The command line used to run vampire on a problem
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VampireInvocation {
+    pub binary: PathBuf,
+    pub arguments: Vec<String>,
+}
+
+impl Display for VampireInvocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.binary.display())?;
+        for argument in &self.arguments {
+            write!(f, " {argument}")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct VampireReport {
     pub problem: Problem,
+    pub invocation: VampireInvocation,
     pub output: VampireOutput,
     pub elapsed_time: Duration,
+    pub termination: Termination,
 }
 
 impl Report for VampireReport {
+    fn problem(&self) -> &Problem {
+        &self.problem
+    }
+
     fn status(&self) -> Result<Status, StatusExtractionError> {
-        self.output.stdout.parse()
+        match self.termination {
+            Termination::Exited => self.output.stdout.parse(),
+            Termination::TimedOut => Ok(Status::Failure(Failure::TimeOut)),
+            Termination::Cancelled => Ok(Status::Failure(Failure::UserTerminated)),
+        }
+    }
+
+    fn elapsed_time(&self) -> Duration {
+        self.elapsed_time
+    }
+
+    fn stdout(&self) -> String {
+        self.output.stdout.clone()
+    }
+
+    fn stderr(&self) -> String {
+        self.output.stderr.clone()
     }
 }
 
@@ -60,6 +99,9 @@ impl Display for VampireReport {
         writeln!(f, "--- {} ---", self.problem.name)?;
         writeln!(f)?;
 
+        writeln!(f, "invocation: {}", self.invocation)?;
+        writeln!(f)?;
+
         writeln!(f, "axioms:")?;
         for axiom in self.problem.axioms() {
             writeln!(f, "    {}", axiom.formula)?;
@@ -79,13 +121,144 @@ impl Display for VampireReport {
     }
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum VampireMode {
+    #[default]
+    Casc,
+    #[value(name = "casc_sat")]
+    CascSat,
+    Portfolio,
+}
+
+impl Display for VampireMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VampireMode::Casc => write!(f, "casc"),
+            VampireMode::CascSat => write!(f, "casc_sat"),
+            VampireMode::Portfolio => write!(f, "portfolio"),
+        }
+    }
+}
+
+/// A memory limit in MiB for the problems of the given name, or for all problems if no name is given
+///
+/// A name also covers the problems that a problem of this name is decomposed into, e.g. `forward`
+/// covers `forward_0` and `forward_outline_1_0`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MemoryLimit {
+    pub problem: Option<String>,
+    pub mebibytes: usize,
+}
+
+impl MemoryLimit {
+    fn applies_to(&self, problem: &str) -> bool {
+        match &self.problem {
+            None => true,
+            Some(name) => problem
+                .strip_prefix(name.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('_')),
+        }
+    }
+}
+
+impl Display for MemoryLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.problem {
+            Some(problem) => write!(f, "{problem}={}", self.mebibytes),
+            None => write!(f, "{}", self.mebibytes),
+        }
+    }
+}
+
+impl FromStr for MemoryLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (problem, mebibytes) = match s.split_once('=') {
+            Some((problem, mebibytes)) => (Some(problem.to_string()), mebibytes),
+            None => (None, s),
+        };
+
+        if problem.as_ref().is_some_and(String::is_empty) {
+            return Err(format!(
+                "no problem was provided for the memory limit `{s}`"
+            ));
+        }
+
+        Ok(MemoryLimit {
+            problem,
+            mebibytes: mebibytes
+                .parse()
+                .map_err(|_| format!("expected `[<problem>=]<mebibytes>`, found `{s}`"))?,
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Vampire {
+    pub binary: PathBuf,
+    pub mode: VampireMode,
     pub time_limit: usize,
+    /// The number of seconds vampire may exceed its time limit before it is killed
+    pub grace_period: usize,
+    /// The memory limits, of which the one with the longest matching problem name applies to a
+    /// problem, or vampire's default if none applies
+    pub memory_limits: Vec<MemoryLimit>,
+    /// Additional arguments passed verbatim to vampire
+    pub arguments: Vec<String>,
     pub instances: usize,
     pub cores: usize,
 }
 
+impl Vampire {
+    /// The memory limit in MiB that applies to the problem of the given name, if any
+    ///
+    /// Later limits take precedence over earlier ones of the same name.
+    pub fn memory_limit(&self, problem: &str) -> Option<usize> {
+        self.memory_limits
+            .iter()
+            .filter(|limit| limit.applies_to(problem))
+            .max_by_key(|limit| limit.problem.as_ref().map_or(0, |name| name.len() + 1))
+            .map(|limit| limit.mebibytes)
+    }
+
+    pub fn invocation(&self, problem: &str) -> VampireInvocation {
+        let mut arguments = vec![
+            "--mode".to_string(),
+            self.mode.to_string(),
+            "--time_limit".to_string(),
+            self.time_limit.to_string(),
+            "--cores".to_string(),
+            self.cores().to_string(),
+            "--proof".to_string(),
+            "tptp".to_string(),
+            "--output_axiom_names".to_string(),
+            "on".to_string(),
+        ];
+
+        if let Some(memory_limit) = self.memory_limit(problem) {
+            arguments.extend(["--memory_limit".to_string(), memory_limit.to_string()]);
+        }
+
+        arguments.extend(self.arguments.iter().cloned());
+
+        VampireInvocation {
+            binary: self.binary.clone(),
+            arguments,
+        }
+    }
+}
+
+impl Display for Vampire {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} --mode {}", self.binary.display(), self.mode)?;
+        if !self.arguments.is_empty() {
+            write!(f, " {}", self.arguments.iter().format(" "))?;
+        }
+        Ok(())
+    }
+}
+
 impl Prover for Vampire {
     type Error = VampireError;
     type Report = VampireReport;
@@ -106,37 +279,123 @@ impl Prover for Vampire {
         }
     }
 
-    fn prove(&self, problem: Problem) -> Result<Self::Report, Self::Error> {
+    fn identity(&self) -> String {
+        let mut identity = self.invocation("").to_string();
+        for limit in self
+            .memory_limits
+            .iter()
+            .filter(|limit| limit.problem.is_some())
+        {
+            identity.push_str(&format!(" --memory_limit {limit}"));
+        }
+        identity
+    }
+
+    fn prove_cancellable(
+        &self,
+        problem: Problem,
+        cancellation: &Cancellation,
+    ) -> Result<Self::Report, Self::Error> {
         let start_time = Instant::now();
 
-        let mut child = Command::new("vampire")
-            .args([
-                "--mode",
-                "casc",
-                "--time_limit",
-                &self.time_limit.to_string(),
-                "--cores",
-                &self.cores().to_string(),
-            ])
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(VampireError::UnableToSpawn)?;
-
-        let mut stdin = child.stdin.take().unwrap();
-        write!(stdin, "{problem}").map_err(VampireError::UnableToWrite)?;
-        drop(stdin);
-
-        let output = child
-            .wait_with_output()
-            .map_err(VampireError::UnableToWait)?
-            .try_into()?;
+        let invocation = self.invocation(&problem.name);
+
+        let output = process::run(
+            Command::new(&invocation.binary).args(&invocation.arguments),
+            problem.to_string().into_bytes(),
+            Some(Duration::from_secs(
+                (self.time_limit + self.grace_period) as u64,
+            )),
+            cancellation,
+        )
+        .map_err(|e| VampireError::UnableToRun(invocation.to_string(), e))?;
+
+        let termination = output.termination;
+        let output = VampireOutput::from_bytes(output.stdout, output.stderr)?;
 
         Ok(VampireReport {
             problem,
+            invocation,
             output,
             elapsed_time: start_time.elapsed(),
+            termination,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{MemoryLimit, Vampire, VampireInvocation, VampireMode};
+
+    #[test]
+    fn test_invocation() {
+        let vampire = Vampire {
+            binary: "/opt/vampire/bin/vampire_z3".into(),
+            mode: VampireMode::CascSat,
+            time_limit: 30,
+            grace_period: 5,
+            memory_limits: vec![MemoryLimit {
+                problem: None,
+                mebibytes: 4096,
+            }],
+            arguments: vec!["--show_options".into(), "on".into()],
+            instances: 1,
+            cores: 2,
+        };
+
+        assert_eq!(
+            vampire.invocation("forward_0"),
+            VampireInvocation {
+                binary: "/opt/vampire/bin/vampire_z3".into(),
+                arguments: [
+                    "--mode",
+                    "casc_sat",
+                    "--time_limit",
+                    "30",
+                    "--cores",
+                    "2",
+                    "--proof",
+                    "tptp",
+                    "--output_axiom_names",
+                    "on",
+                    "--memory_limit",
+                    "4096",
+                    "--show_options",
+                    "on"
+                ]
+                .map(String::from)
+                .to_vec(),
+            }
+        );
+        assert_eq!(
+            vampire.to_string(),
+            "/opt/vampire/bin/vampire_z3 --mode casc_sat --show_options on"
+        );
+    }
+
+    #[test]
+    fn test_memory_limits() {
+        let vampire = Vampire {
+            binary: "vampire".into(),
+            mode: VampireMode::Casc,
+            time_limit: 30,
+            grace_period: 5,
+            memory_limits: ["4096", "backward=8192", "backward_outline=2048", "2000"]
+                .map(|limit| limit.parse().unwrap())
+                .to_vec(),
+            arguments: vec![],
+            instances: 1,
+            cores: 1,
+        };
+
+        assert_eq!(vampire.memory_limit("forward_0"), Some(2000));
+        assert_eq!(vampire.memory_limit("backward"), Some(8192));
+        assert_eq!(vampire.memory_limit("backward_1"), Some(8192));
+        assert_eq!(vampire.memory_limit("backward_outline_0_1"), Some(2048));
+        assert_eq!(vampire.memory_limit("backwards_0"), Some(2000));
+
+        assert!("=4096".parse::<MemoryLimit>().is_err());
+        assert!("backward=".parse::<MemoryLimit>().is_err());
+        assert!("lots".parse::<MemoryLimit>().is_err());
+    }
+}
@@ -0,0 +1,475 @@
+use {
+    crate::verifying::{
+        problem::{AnnotatedFormula, Problem},
+        prover::{cache::CacheStatistics, Report, Verdict},
+    },
+    anyhow::{Context, Result},
+    serde::{Serialize, Serializer},
+    std::{
+        fmt::Display,
+        fs::{self, File},
+        io::BufWriter,
+        path::Path,
+        time::Duration,
+    },
+};
+
+/// A formula of a problem in anthem's syntax
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FormulaRecord {
+    pub name: String,
+    pub formula: String,
+}
+
+impl From<AnnotatedFormula> for FormulaRecord {
+    fn from(annotated_formula: AnnotatedFormula) -> Self {
+        FormulaRecord {
+            name: annotated_formula.name,
+            formula: annotated_formula.formula.to_string(),
+        }
+    }
+}
+
+/// The outcome of proving a single problem
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ProblemRecord {
+    pub name: String,
+    /// The direction of the proof the problem belongs to
+    pub direction: Option<String>,
+    pub axioms: Vec<FormulaRecord>,
+    pub conjectures: Vec<FormulaRecord>,
+    /// The SZS status reported by the prover, if it reported any
+    pub status: Option<String>,
+    pub verdict: Verdict,
+    pub elapsed_ms: Option<u128>,
+    /// Whether the outcome was taken from the proof cache
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_axioms: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The problems whose conjectures this problem relies on, if it was skipped because they were not proven
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped_because: Option<Vec<String>>,
+    /// The output of the prover, which JSON reports only include alongside errors
+    #[serde(skip)]
+    pub output: Option<ProverOutput>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ProverOutput {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl ProblemRecord {
+    fn new(problem: &Problem, verdict: Verdict) -> Self {
+        ProblemRecord {
+            name: problem.name.clone(),
+            direction: problem.direction().map(|direction| direction.to_string()),
+            axioms: problem.axioms().into_iter().map(Into::into).collect(),
+            conjectures: problem.conjectures().into_iter().map(Into::into).collect(),
+            status: None,
+            verdict,
+            elapsed_ms: None,
+            cached: false,
+            used_axioms: None,
+            error: None,
+            skipped_because: None,
+            output: None,
+        }
+    }
+
+    /// Record the outcome of a prover run, including its output if no SZS status could be determined
+    pub fn from_report(report: &impl Report) -> Self {
+        let output = Some(ProverOutput {
+            stdout: report.stdout().to_string(),
+            stderr: report.stderr().to_string(),
+        });
+
+        match report.status() {
+            Ok(status) => ProblemRecord {
+                status: Some(status.to_string()),
+                elapsed_ms: Some(report.elapsed_time().as_millis()),
+                cached: report.cached(),
+                used_axioms: status
+                    .proves_conjecture()
+                    .then(|| report.used_axioms())
+                    .flatten()
+                    .map(|used_axioms| used_axioms.into_iter().collect()),
+                output,
+                ..ProblemRecord::new(report.problem(), status.verdict())
+            },
+            Err(error) => ProblemRecord {
+                elapsed_ms: Some(report.elapsed_time().as_millis()),
+                error: Some(error.to_string()),
+                output,
+                ..ProblemRecord::new(report.problem(), Verdict::Error)
+            },
+        }
+    }
+
+    /// Record that a problem was skipped, as the conjectures of the given problems it relies on were not proven
+    pub fn skipped(problem: &Problem, premises: Vec<String>) -> Self {
+        ProblemRecord {
+            skipped_because: Some(premises),
+            ..ProblemRecord::new(problem, Verdict::Unknown)
+        }
+    }
+
+    /// Record a failure to run the prover at all
+    pub fn from_error(problem: &Problem, error: impl Display) -> Self {
+        ProblemRecord {
+            error: Some(error.to_string()),
+            ..ProblemRecord::new(problem, Verdict::Error)
+        }
+    }
+}
+
+/// The outcome of a verification, i.e. of proving all problems of a task
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    #[serde(serialize_with = "serialize_problems")]
+    pub problems: Vec<ProblemRecord>,
+    /// The warnings reported while decomposing the task into problems
+    pub warnings: Vec<String>,
+    pub verdict: Verdict,
+    pub elapsed_ms: Option<u128>,
+    /// The statistics of the proof cache, if one was used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheStatistics>,
+}
+
+impl Summary {
+    pub fn new(warnings: Vec<String>) -> Self {
+        Summary {
+            problems: vec![],
+            warnings,
+            verdict: Verdict::Proved,
+            elapsed_ms: None,
+            cache: None,
+        }
+    }
+
+    pub fn add(&mut self, record: ProblemRecord) {
+        self.verdict = self.verdict.max(record.verdict);
+        self.problems.push(record);
+    }
+
+    /// Add the problems and warnings of another summary, prefixing their names with the given one
+    pub fn extend(&mut self, prefix: &str, other: Summary) {
+        for mut record in other.problems {
+            record.name = format!("{prefix}/{}", record.name);
+            self.add(record);
+        }
+        self.warnings.extend(
+            other
+                .warnings
+                .into_iter()
+                .map(|warning| format!("{prefix}: {warning}")),
+        );
+        if let Some(statistics) = other.cache {
+            self.cache
+                .get_or_insert_with(Default::default)
+                .add(statistics);
+        }
+    }
+
+    pub fn finish(&mut self, elapsed_time: Duration) {
+        self.elapsed_ms = Some(elapsed_time.as_millis());
+    }
+
+    pub fn to_json_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("could not create file `{}`", path.display()))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)
+            .with_context(|| format!("could not write file `{}`", path.display()))
+    }
+
+    /// Render the summary as a JUnit test suite with one test case per problem
+    ///
+    /// Proven problems are successful test cases, problems with any other SZS status are failures,
+    /// problems on which the prover failed are errors, and problems skipped under `--fail-fast` are skipped.
+    pub fn to_junit(&self) -> String {
+        let count = |verdicts: &[Verdict]| {
+            self.problems
+                .iter()
+                .filter(|problem| {
+                    problem.skipped_because.is_none() && verdicts.contains(&problem.verdict)
+                })
+                .count()
+        };
+        let tests = self.problems.len();
+        let failures = count(&[Verdict::Unknown, Verdict::Disproved]);
+        let errors = count(&[Verdict::Error]);
+        let skipped = self
+            .problems
+            .iter()
+            .filter(|problem| problem.skipped_because.is_some())
+            .count();
+        let time = seconds(self.elapsed_ms);
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites name=\"anthem\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time}\">\n"
+        ));
+        xml.push_str(&format!(
+            "  <testsuite name=\"verify\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" skipped=\"{skipped}\" time=\"{time}\">\n"
+        ));
+
+        xml.push_str("    <properties>\n");
+        xml.push_str(&format!(
+            "      <property name=\"verdict\" value=\"{}\"/>\n",
+            self.verdict
+        ));
+        if let Some(cache) = &self.cache {
+            xml.push_str(&format!(
+                "      <property name=\"cache\" value=\"{cache}\"/>\n"
+            ));
+        }
+        for warning in &self.warnings {
+            xml.push_str(&format!(
+                "      <property name=\"warning\" value=\"{}\"/>\n",
+                escape(warning)
+            ));
+        }
+        xml.push_str("    </properties>\n");
+
+        for problem in &self.problems {
+            let classname = match &problem.direction {
+                Some(direction) => format!("verify.{direction}"),
+                None => "verify".to_string(),
+            };
+            xml.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{classname}\" time=\"{}\">\n",
+                escape(&problem.name),
+                seconds(problem.elapsed_ms),
+            ));
+
+            xml.push_str("      <properties>\n");
+            if problem.cached {
+                xml.push_str("        <property name=\"cached\" value=\"true\"/>\n");
+            }
+            for conjecture in &problem.conjectures {
+                xml.push_str(&format!(
+                    "        <property name=\"conjecture\" value=\"{}: {}\"/>\n",
+                    escape(&conjecture.name),
+                    escape(&conjecture.formula),
+                ));
+            }
+            xml.push_str("      </properties>\n");
+
+            let status = problem.status.as_deref().unwrap_or("none");
+            match (&problem.skipped_because, problem.verdict) {
+                (Some(premises), _) => xml.push_str(&format!(
+                    "      <skipped message=\"the conjectures of {} were not proven\"/>\n",
+                    escape(&premises.join(", "))
+                )),
+                (None, Verdict::Proved) => (),
+                (None, Verdict::Unknown | Verdict::Disproved) => xml.push_str(&format!(
+                    "      <failure type=\"{status}\" message=\"the prover reported the SZS status {status}\"/>\n"
+                )),
+                (None, Verdict::Error) => xml.push_str(&format!(
+                    "      <error type=\"{status}\" message=\"{}\"/>\n",
+                    escape(problem.error.as_deref().unwrap_or("the prover failed"))
+                )),
+            }
+
+            if let Some(output) = &problem.output {
+                xml.push_str(&format!(
+                    "      <system-out>{}</system-out>\n",
+                    escape(&output.stdout)
+                ));
+                xml.push_str(&format!(
+                    "      <system-err>{}</system-err>\n",
+                    escape(&output.stderr)
+                ));
+            }
+
+            xml.push_str("    </testcase>\n");
+        }
+
+        xml.push_str("  </testsuite>\n");
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+
+    pub fn to_junit_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        fs::write(path, self.to_junit())
+            .with_context(|| format!("could not write file `{}`", path.display()))
+    }
+}
+
+/// Serialize the records of problems, including the output of the prover only alongside errors
+fn serialize_problems<S: Serializer>(
+    problems: &[ProblemRecord],
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct WithOutput<'a> {
+        #[serde(flatten)]
+        record: &'a ProblemRecord,
+        #[serde(flatten, skip_serializing_if = "Option::is_none")]
+        output: Option<&'a ProverOutput>,
+    }
+
+    serializer.collect_seq(problems.iter().map(|record| WithOutput {
+        record,
+        output: record.output.as_ref().filter(|_| record.error.is_some()),
+    }))
+}
+
+fn seconds(elapsed_ms: Option<u128>) -> String {
+    let elapsed_ms = elapsed_ms.unwrap_or_default();
+    format!("{}.{:03}", elapsed_ms / 1000, elapsed_ms % 1000)
+}
+
+/// Escape text for use in XML attributes and elements, dropping characters XML cannot represent
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{ProblemRecord, ProverOutput, Summary},
+        crate::verifying::{
+            problem::{AnnotatedFormula, Problem, Role},
+            prover::{testing::TestReport, Status, Success, Verdict},
+        },
+        std::time::Duration,
+    };
+
+    fn problem(name: &str) -> Problem {
+        Problem::with_name(name).add_annotated_formulas([
+            AnnotatedFormula {
+                name: "assumption".into(),
+                role: Role::Axiom,
+                formula: "p(a)".parse().unwrap(),
+                provenance: None,
+            },
+            AnnotatedFormula {
+                name: "goal".into(),
+                role: Role::Conjecture,
+                formula: "p(a) -> q(a)".parse().unwrap(),
+                provenance: None,
+            },
+        ])
+    }
+
+    fn output(stdout: &str) -> Option<ProverOutput> {
+        Some(ProverOutput {
+            stdout: stdout.into(),
+            stderr: "".into(),
+        })
+    }
+
+    #[test]
+    fn test_summary_to_json() {
+        let mut summary = Summary::new(vec!["a warning".into()]);
+        summary.add(ProblemRecord::from_error(
+            &problem("forward_problem_0"),
+            "unable to run vampire",
+        ));
+        summary.add(ProblemRecord {
+            output: output("% SZS status Garbage"),
+            ..ProblemRecord::from_error(&problem("forward_problem_1"), "unrecognized status")
+        });
+        summary.add(ProblemRecord {
+            elapsed_ms: None,
+            ..ProblemRecord::from_report(&TestReport {
+                problem: problem("backward_problem_0"),
+                status: Status::Success(Success::Theorem),
+                elapsed_time: Duration::ZERO,
+            })
+        });
+
+        assert_eq!(summary.verdict, Verdict::Error);
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            serde_json::json!({
+                "problems": [{
+                    "name": "forward_problem_0",
+                    "direction": "forward",
+                    "axioms": [{"name": "assumption", "formula": "p(a)"}],
+                    "conjectures": [{"name": "goal", "formula": "p(a) -> q(a)"}],
+                    "status": null,
+                    "verdict": "error",
+                    "elapsed_ms": null,
+                    "error": "unable to run vampire",
+                }, {
+                    "name": "forward_problem_1",
+                    "direction": "forward",
+                    "axioms": [{"name": "assumption", "formula": "p(a)"}],
+                    "conjectures": [{"name": "goal", "formula": "p(a) -> q(a)"}],
+                    "status": null,
+                    "verdict": "error",
+                    "elapsed_ms": null,
+                    "error": "unrecognized status",
+                    "stdout": "% SZS status Garbage",
+                    "stderr": "",
+                }, {
+                    "name": "backward_problem_0",
+                    "direction": "backward",
+                    "axioms": [{"name": "assumption", "formula": "p(a)"}],
+                    "conjectures": [{"name": "goal", "formula": "p(a) -> q(a)"}],
+                    "status": "Theorem",
+                    "verdict": "proved",
+                    "elapsed_ms": null,
+                }],
+                "warnings": ["a warning"],
+                "verdict": "error",
+                "elapsed_ms": null,
+            })
+        );
+    }
+
+    #[test]
+    fn test_summary_to_junit() {
+        let mut summary = Summary::new(vec![]);
+        summary.add(ProblemRecord::from_report(&TestReport {
+            problem: problem("backward_problem_1"),
+            status: Status::Success(Success::CounterSatisfiable),
+            elapsed_time: Duration::from_millis(1234),
+        }));
+        summary.finish(Duration::from_millis(2000));
+
+        assert_eq!(summary.verdict, Verdict::Disproved);
+        assert_eq!(
+            summary.to_junit(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="anthem" tests="1" failures="1" errors="0" time="2.000">
+  <testsuite name="verify" tests="1" failures="1" errors="0" skipped="0" time="2.000">
+    <properties>
+      <property name="verdict" value="disproved"/>
+    </properties>
+    <testcase name="backward_problem_1" classname="verify.backward" time="1.234">
+      <properties>
+        <property name="conjecture" value="goal: p(a) -&gt; q(a)"/>
+      </properties>
+      <failure type="CounterSatisfiable" message="the prover reported the SZS status CounterSatisfiable"/>
+      <system-out>% SZS status CounterSatisfiable for backward_problem_1</system-out>
+      <system-err></system-err>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}
@@ -8,7 +8,14 @@ use {
             with_warnings::{Result, WithWarnings},
         },
         syntax_tree::{asp, fol},
-        translating::{completion::completion, tau_star::tau_star},
+        translating::{
+            loop_formulas::loop_formulas,
+            natural::natural,
+            provenance::{
+                trace_completion, trace_natural, trace_tau_star, Provenance, TracedTheory,
+            },
+            tau_star::tau_star,
+        },
         verifying::{
             outline::{GeneralLemma, ProofOutline, ProofOutlineError, ProofOutlineWarning},
             problem::{self, Problem},
@@ -76,9 +83,63 @@ impl RenamePredicates for fol::Atom {
     }
 }
 
+/// The predicate defined by a completed definition or a definition of a proof outline
+fn head_predicate(formula: &fol::Formula) -> Option<fol::Predicate> {
+    match formula {
+        fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Equivalence,
+            lhs,
+            rhs: _,
+        } => match **lhs {
+            fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(ref a)) => Some(a.predicate()),
+            _ => None,
+        },
+        fol::Formula::QuantifiedFormula {
+            quantification:
+                fol::Quantification {
+                    quantifier: fol::Quantifier::Forall,
+                    variables: _,
+                },
+            formula,
+        } => head_predicate(formula),
+        _ => None,
+    }
+}
+
+/// The definitions of a proof outline the given conclusions rely on, such as those of ranking predicates
+fn required_definitions(
+    definitions: &[fol::AnnotatedFormula],
+    conclusions: &[problem::AnnotatedFormula],
+) -> Vec<problem::AnnotatedFormula> {
+    let mut required: IndexSet<_> = conclusions
+        .iter()
+        .flat_map(|conclusion| conclusion.formula.predicates())
+        .collect();
+
+    // Later definitions may only rely on earlier ones
+    let mut result = Vec::new();
+    for definition in definitions.iter().rev() {
+        if head_predicate(&definition.formula).is_some_and(|p| required.contains(&p)) {
+            required.extend(definition.formula.predicates());
+            result.push(
+                definition
+                    .clone()
+                    .into_problem_formula(problem::Role::Axiom),
+            );
+        }
+    }
+    result.reverse();
+    result
+}
+
 #[derive(Error, Debug)]
 pub enum ExternalEquivalenceTaskWarning {
     NonTightProgram(asp::Program),
+    RankingPredicates {
+        program: asp::Program,
+        rankings: Vec<fol::Predicate>,
+        direction: fol::Direction,
+    },
     InconsistentDirectionAnnotation(fol::AnnotatedFormula),
     InvalidRoleWithinUserGuide(fol::AnnotatedFormula),
     DefinitionWithWarning(#[from] ProofOutlineWarning),
@@ -91,6 +152,12 @@ impl Display for ExternalEquivalenceTaskWarning {
                 writeln!(f, "the following program is not tight: ")?;
                 writeln!(f, "{program}")
             },
+            ExternalEquivalenceTaskWarning::RankingPredicates { program, rankings, direction } => {
+                write!(f, "the following program is not tight, so its loop formulas are verified with the ranking predicates ")?;
+                write!(f, "{}", rankings.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", "))?;
+                writeln!(f, ", which may only be defined in the {direction} direction of the proof outline: ")?;
+                writeln!(f, "{program}")
+            },
             ExternalEquivalenceTaskWarning::InconsistentDirectionAnnotation(formula) => {
                 let proof_direction = match formula.direction {
                     fol::Direction::Forward => fol::Direction::Backward,
@@ -113,7 +180,6 @@ impl Display for ExternalEquivalenceTaskWarning {
 
 #[derive(Error, Debug)]
 pub enum ExternalEquivalenceTaskError {
-    NonTightProgram(asp::Program),
     ProgramContainsPrivateRecursion(asp::Program),
     InputOutputPredicatesOverlap(Vec<fol::Predicate>),
     InputPredicateInRuleHead(Vec<fol::Predicate>),
@@ -121,18 +187,15 @@ pub enum ExternalEquivalenceTaskError {
     OutputPredicateInSpecificationAssumption(Vec<fol::Predicate>),
     PlaceholdersWithIdenticalNamesDifferentSorts(String),
     AssumptionContainsNonInputSymbols(fol::AnnotatedFormula),
+    InvalidRankingDefinition(fol::AnnotatedFormula),
     ProofOutlineError(#[from] ProofOutlineError),
 }
 
 impl Display for ExternalEquivalenceTaskError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ExternalEquivalenceTaskError::NonTightProgram(program) => {
-                writeln!(f, "the following program is not tight: ")?;
-                writeln!(f, "{program}")
-            }
             ExternalEquivalenceTaskError::ProgramContainsPrivateRecursion(program) => {
-                writeln!(f, "the following program contains private recursion: ")?;
+                writeln!(f, "the following program contains private recursion through negation or choice rules, or its loop formulas were bypassed: ")?;
                 writeln!(f, "{program}")
             }
             ExternalEquivalenceTaskError::InputOutputPredicatesOverlap(predicates) => {
@@ -202,6 +265,9 @@ impl Display for ExternalEquivalenceTaskError {
             ExternalEquivalenceTaskError::AssumptionContainsNonInputSymbols(formula) => {
                 writeln!(f, "the following assumption contains a predicate that is not an input symbol: {formula}")
             }
+            ExternalEquivalenceTaskError::InvalidRankingDefinition(formula) => {
+                writeln!(f, "the following definition defines a ranking predicate of a loop formula in a direction of the proof in which the loop formula is an axiom: {formula}")
+            }
             ExternalEquivalenceTaskError::ProofOutlineError(_) => {
                 writeln!(f, "the given proof outline contains errors")
             }
@@ -218,25 +284,25 @@ pub struct ExternalEquivalenceTask {
     pub decomposition: Decomposition,
     pub direction: fol::Direction,
     pub bypass_tightness: bool,
+    pub natural: bool,
     pub simplify: bool,
     pub break_equivalences: bool,
 }
 
 impl ExternalEquivalenceTask {
-    fn ensure_program_tightness(
+    /// Returns whether the completion of the program has to be extended by loop formulas
+    fn requires_loop_formulas(
         &self,
         program: &asp::Program,
-    ) -> Result<(), ExternalEquivalenceTaskWarning, ExternalEquivalenceTaskError> {
+    ) -> Result<bool, ExternalEquivalenceTaskWarning, ExternalEquivalenceTaskError> {
         if program.is_tight() {
-            Ok(WithWarnings::flawless(()))
+            Ok(WithWarnings::flawless(false))
         } else if self.bypass_tightness {
-            Ok(WithWarnings::flawless(()).add_warning(
+            Ok(WithWarnings::flawless(false).add_warning(
                 ExternalEquivalenceTaskWarning::NonTightProgram(program.clone()),
             ))
         } else {
-            Err(ExternalEquivalenceTaskError::NonTightProgram(
-                program.clone(),
-            ))
+            Ok(WithWarnings::flawless(true))
         }
     }
 
@@ -251,7 +317,12 @@ impl ExternalEquivalenceTask {
             .map(asp::Predicate::from)
             .collect();
 
-        if program.has_private_recursion(&private_predicates) {
+        // Positive private recursion is captured by the loop formulas of the private predicates,
+        // which determine them uniquely in terms of the public predicates
+        if program.has_private_recursion(&private_predicates)
+            && (self.bypass_tightness
+                || program.has_nonpositive_private_recursion(&private_predicates))
+        {
             Err(ExternalEquivalenceTaskError::ProgramContainsPrivateRecursion(program.clone()))
         } else {
             Ok(WithWarnings::flawless(()))
@@ -411,15 +482,19 @@ impl Task for ExternalEquivalenceTask {
         let mut warnings = Vec::new();
 
         self.ensure_input_and_output_predicates_are_disjoint()?;
-        warnings.extend(self.ensure_program_tightness(&self.program)?.warnings);
+        let program_tightness = self.requires_loop_formulas(&self.program)?;
+        warnings.extend(program_tightness.warnings);
         self.ensure_absence_of_private_recursion(&self.program, &program_private_predicates)?;
         self.ensure_rule_heads_do_not_contain_input_predicates(&self.program)?;
         self.ensure_placeholder_name_uniqueness()?;
         self.ensure_assumptions_only_contain_input_symbols(&self.user_guide.formulas())?;
 
+        let mut specification_requires_loop_formulas = false;
         match self.specification {
             Either::Left(ref program) => {
-                warnings.extend(self.ensure_program_tightness(program)?.warnings);
+                let specification_tightness = self.requires_loop_formulas(program)?;
+                warnings.extend(specification_tightness.warnings);
+                specification_requires_loop_formulas = specification_tightness.data;
                 self.ensure_absence_of_private_recursion(
                     program,
                     &specification_private_predicates,
@@ -434,80 +509,171 @@ impl Task for ExternalEquivalenceTask {
             }
         }
 
-        fn head_predicate(formula: &fol::Formula) -> Option<fol::Predicate> {
-            match formula {
-                fol::Formula::BinaryFormula {
-                    connective: fol::BinaryConnective::Equivalence,
-                    lhs,
-                    rhs: _,
-                } => match **lhs {
-                    fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(ref a)) => {
-                        Some(a.predicate())
-                    }
-                    _ => None,
-                },
-                fol::Formula::QuantifiedFormula {
-                    quantification:
-                        fol::Quantification {
-                            quantifier: fol::Quantifier::Forall,
-                            variables: _,
-                        },
-                    formula,
-                } => head_predicate(formula),
-                _ => None,
-            }
-        }
-
-        let control_translate = |theory: fol::Theory| {
+        // The provenance of the completed definitions and constraints is recorded by their names
+        let control_translate = |theory: TracedTheory| {
             let mut constraint_counter = 0..;
+            let mut provenance = IndexMap::new();
             let formulas = theory
+                .theory
                 .formulas
                 .into_iter()
-                .map(|formula| match head_predicate(&formula) {
-                    Some(p) if public_predicates.contains(&p) => fol::AnnotatedFormula {
-                        role: fol::Role::Spec,
-                        direction: fol::Direction::Universal,
-                        name: format!("completed_definition_of_{}_{}", p.symbol, p.arity),
-                        formula,
-                    },
-                    Some(p) => fol::AnnotatedFormula {
-                        role: fol::Role::Assumption,
-                        direction: fol::Direction::Universal,
-                        name: format!("completed_definition_of_{}_{}", p.symbol, p.arity),
-                        formula,
-                    },
-                    None => fol::AnnotatedFormula {
-                        role: fol::Role::Spec,
-                        direction: fol::Direction::Universal,
-                        name: format!("constraint_{}", constraint_counter.next().unwrap()),
-                        formula,
-                    },
+                .zip(theory.provenance)
+                .map(|(formula, origin)| {
+                    let annotated_formula = match head_predicate(&formula) {
+                        Some(p) if public_predicates.contains(&p) => fol::AnnotatedFormula {
+                            role: fol::Role::Spec,
+                            direction: fol::Direction::Universal,
+                            name: format!("completed_definition_of_{}_{}", p.symbol, p.arity),
+                            formula,
+                        },
+                        Some(p) => fol::AnnotatedFormula {
+                            role: fol::Role::Assumption,
+                            direction: fol::Direction::Universal,
+                            name: format!("completed_definition_of_{}_{}", p.symbol, p.arity),
+                            formula,
+                        },
+                        None => fol::AnnotatedFormula {
+                            role: fol::Role::Spec,
+                            direction: fol::Direction::Universal,
+                            name: format!("constraint_{}", constraint_counter.next().unwrap()),
+                            formula,
+                        },
+                    };
+                    provenance.insert(annotated_formula.name.clone(), origin);
+                    annotated_formula
                 })
                 .collect();
-            fol::Specification { formulas }
+            (fol::Specification { formulas }, provenance)
+        };
+
+        let translate = if self.natural { natural } else { tau_star };
+        let trace = if self.natural {
+            trace_natural
+        } else {
+            trace_tau_star
         };
 
+        // Loop formulas are specifications like completed definitions, which use fresh ranking predicates,
+        // but the loop formulas of components of private predicates are assumptions like their completed definitions
+        let loop_translate = |program: &asp::Program, private_predicates: &IndexSet<_>| {
+            let theory = translate(program.clone()).replace_placeholders(&placeholders);
+            let predicates = theory.predicates();
+
+            let mut formulas = Vec::new();
+            let mut public_rankings = IndexSet::new();
+            let mut private_rankings = IndexSet::new();
+            for component in program.recursive_components() {
+                let component: IndexSet<_> =
+                    component.into_iter().map(fol::Predicate::from).collect();
+                let private = component.is_subset(private_predicates);
+
+                let loop_formulas = loop_formulas(theory.clone(), &[component])
+                    .expect("the translation did not create a completable theory");
+                let rankings: Vec<_> = loop_formulas
+                    .predicates()
+                    .difference(&predicates)
+                    .cloned()
+                    .collect();
+                if private {
+                    private_rankings.extend(rankings);
+                } else {
+                    public_rankings.extend(rankings);
+                }
+
+                for formula in loop_formulas.formulas {
+                    formulas.push(fol::AnnotatedFormula {
+                        role: if private {
+                            fol::Role::Assumption
+                        } else {
+                            fol::Role::Spec
+                        },
+                        direction: fol::Direction::Universal,
+                        name: format!("loop_formula_{}", formulas.len()),
+                        formula,
+                    });
+                }
+            }
+
+            (
+                fol::Specification { formulas },
+                public_rankings,
+                private_rankings,
+            )
+        };
+
+        let mut left_rankings = IndexSet::new();
+        let mut left_provenance = IndexMap::new();
         let left = match self.specification {
-            Either::Left(program) => control_translate(
-                completion(tau_star(program).replace_placeholders(&placeholders))
-                    .expect("tau_star did not create a completable theory"),
-            ),
+            Either::Left(ref program) => {
+                let (mut left, provenance) = control_translate(
+                    trace_completion(
+                        trace(program.clone()).map(|f| f.replace_placeholders(&placeholders)),
+                    )
+                    .expect("the translation did not create a completable theory"),
+                );
+                left_provenance = provenance;
+                if specification_requires_loop_formulas {
+                    let (loop_formulas, rankings, _) =
+                        loop_translate(program, &specification_private_predicates);
+                    left.formulas.extend(loop_formulas.formulas);
+                    if !rankings.is_empty() {
+                        warnings.push(ExternalEquivalenceTaskWarning::RankingPredicates {
+                            program: program.clone(),
+                            rankings: rankings.iter().cloned().collect(),
+                            direction: fol::Direction::Backward,
+                        });
+                    }
+                    left_rankings = rankings;
+                }
+                left
+            }
             Either::Right(specification) => specification.replace_placeholders(&placeholders),
         };
 
-        let right = control_translate(
-            completion(tau_star(self.program).replace_placeholders(&placeholders))
-                .expect("tau_star did not create a completable theory"),
+        let (mut right, right_provenance) = control_translate(
+            trace_completion(
+                trace(self.program.clone()).map(|f| f.replace_placeholders(&placeholders)),
+            )
+            .expect("the translation did not create a completable theory"),
         );
+        let mut right_rankings = IndexSet::new();
+        let mut right_private_rankings = IndexSet::new();
+        if program_tightness.data {
+            let (loop_formulas, rankings, private_rankings) =
+                loop_translate(&self.program, &program_private_predicates);
+            right.formulas.extend(loop_formulas.formulas);
+            right_rankings = rankings;
+            right_private_rankings = private_rankings;
+        }
 
         // TODO: Warn when a conflict between private predicates is encountered
         // TODO: Check if renaming creates new conflicts
-        let right = right.rename_predicates(
-            &specification_private_predicates
-                .intersection(&program_private_predicates)
-                .map(|p| (p.clone(), "p".to_string()))
-                .collect(),
-        );
+        let left_predicates = left.predicates();
+        let mapping: IndexMap<_, _> = specification_private_predicates
+            .intersection(&program_private_predicates)
+            .chain(right_rankings.intersection(&left_predicates))
+            .chain(right_private_rankings.intersection(&left_predicates))
+            .map(|p| (p.clone(), "p".to_string()))
+            .collect();
+        let right = right.rename_predicates(&mapping);
+        let right_rankings: IndexSet<_> = right_rankings
+            .into_iter()
+            .map(|p| match mapping.get(&p) {
+                Some(name_extension) => fol::Predicate {
+                    symbol: format!("{}_{}", p.symbol, name_extension),
+                    arity: p.arity,
+                },
+                None => p,
+            })
+            .collect();
+
+        if !right_rankings.is_empty() {
+            warnings.push(ExternalEquivalenceTaskWarning::RankingPredicates {
+                program: self.program.clone(),
+                rankings: right_rankings.iter().cloned().collect(),
+                direction: fol::Direction::Forward,
+            });
+        }
 
         let mut user_guide_assumptions = Vec::new();
         for formula in self.user_guide.formulas() {
@@ -541,6 +707,8 @@ impl Task for ExternalEquivalenceTask {
         for anf in right.formulas.iter() {
             taken_predicates.extend(anf.formula.predicates());
         }
+        // Ranking predicates are left for the proof outline to define
+        taken_predicates.retain(|p| !left_rankings.contains(p) && !right_rankings.contains(p));
 
         let proof_outline_construction =
             ProofOutline::from_specification(self.proof_outline, taken_predicates, &placeholders)?;
@@ -551,11 +719,28 @@ impl Task for ExternalEquivalenceTask {
                 .map(ExternalEquivalenceTaskWarning::from),
         );
 
+        // Defining a ranking where its loop formulas are axioms would constrain them beyond their existence
+        let proof_outline = proof_outline_construction.data;
+        for (definitions, rankings) in [
+            (&proof_outline.forward_definitions, &left_rankings),
+            (&proof_outline.backward_definitions, &right_rankings),
+        ] {
+            for definition in definitions {
+                if head_predicate(&definition.formula).is_some_and(|p| rankings.contains(&p)) {
+                    return Err(ExternalEquivalenceTaskError::InvalidRankingDefinition(
+                        definition.clone(),
+                    ));
+                }
+            }
+        }
+
         Ok(ValidatedExternalEquivalenceTask {
             left: left.formulas,
             right: right.formulas,
+            left_provenance,
+            right_provenance,
             user_guide_assumptions,
-            proof_outline: proof_outline_construction.data,
+            proof_outline,
             decomposition: self.decomposition,
             direction: self.direction,
             break_equivalences: self.break_equivalences,
@@ -568,6 +753,8 @@ impl Task for ExternalEquivalenceTask {
 struct ValidatedExternalEquivalenceTask {
     pub left: Vec<fol::AnnotatedFormula>, // TODO: Use fol::Specification?
     pub right: Vec<fol::AnnotatedFormula>,
+    pub left_provenance: IndexMap<String, Provenance>,
+    pub right_provenance: IndexMap<String, Provenance>,
     pub user_guide_assumptions: Vec<fol::AnnotatedFormula>,
     pub proof_outline: ProofOutline,
     pub decomposition: Decomposition,
@@ -599,25 +786,30 @@ impl Task for ValidatedExternalEquivalenceTask {
         let mut warnings = Vec::new();
 
         for formula in self.left {
+            let provenance = self.left_provenance.get(&formula.name);
+            let trace = |formula: fol::AnnotatedFormula, role| problem::AnnotatedFormula {
+                provenance: provenance.cloned(),
+                ..formula.into_problem_formula(role)
+            };
             match formula.role {
                 Assumption => match formula.direction {
-                    Universal => stable_premises.push(formula.into_problem_formula(Axiom)),
-                    Forward => forward_premises.push(formula.into_problem_formula(Axiom)),
+                    Universal => stable_premises.push(trace(formula, Axiom)),
+                    Forward => forward_premises.push(trace(formula, Axiom)),
                     Backward => warnings.push(
                         ExternalEquivalenceTaskWarning::InconsistentDirectionAnnotation(formula),
                     ),
                 },
                 Spec => {
                     if matches!(formula.direction, Universal | Forward) {
-                        forward_premises.push(formula.clone().into_problem_formula(Axiom))
+                        forward_premises.push(trace(formula.clone(), Axiom))
                     }
                     if matches!(formula.direction, Universal | Backward) {
                         if self.break_equivalences {
                             for formula in break_equivalences_annotated_formula(formula) {
-                                backward_conclusions.push(formula.into_problem_formula(Conjecture))
+                                backward_conclusions.push(trace(formula, Conjecture))
                             }
                         } else {
-                            backward_conclusions.push(formula.into_problem_formula(Conjecture))
+                            backward_conclusions.push(trace(formula, Conjecture))
                         }
                     }
                 }
@@ -626,25 +818,30 @@ impl Task for ValidatedExternalEquivalenceTask {
         }
 
         for formula in self.right {
+            let provenance = self.right_provenance.get(&formula.name);
+            let trace = |formula: fol::AnnotatedFormula, role| problem::AnnotatedFormula {
+                provenance: provenance.cloned(),
+                ..formula.into_problem_formula(role)
+            };
             match formula.role {
                 Assumption => match formula.direction {
-                    Universal => stable_premises.push(formula.into_problem_formula(Axiom)),
+                    Universal => stable_premises.push(trace(formula, Axiom)),
                     Forward => warnings.push(
                         ExternalEquivalenceTaskWarning::InconsistentDirectionAnnotation(formula),
                     ),
-                    Backward => backward_premises.push(formula.into_problem_formula(Axiom)),
+                    Backward => backward_premises.push(trace(formula, Axiom)),
                 },
                 Spec => {
                     if matches!(formula.direction, Universal | Backward) {
-                        backward_premises.push(formula.clone().into_problem_formula(Axiom))
+                        backward_premises.push(trace(formula.clone(), Axiom))
                     }
                     if matches!(formula.direction, Universal | Forward) {
                         if self.break_equivalences {
                             for formula in break_equivalences_annotated_formula(formula) {
-                                forward_conclusions.push(formula.into_problem_formula(Conjecture))
+                                forward_conclusions.push(trace(formula, Conjecture))
                             }
                         } else {
-                            forward_conclusions.push(formula.into_problem_formula(Conjecture))
+                            forward_conclusions.push(trace(formula, Conjecture))
                         }
                     }
                 }
@@ -689,6 +886,11 @@ impl Task for AssembledExternalEquivalenceTask {
             self.direction,
             fol::Direction::Universal | fol::Direction::Forward
         ) {
+            let definitions = required_definitions(
+                &self.proof_outline.forward_definitions,
+                &self.forward_conclusions,
+            );
+
             let mut axioms = self.stable_premises.clone();
             axioms.extend(self.forward_premises.clone());
             axioms.extend(
@@ -715,6 +917,7 @@ impl Task for AssembledExternalEquivalenceTask {
                 &mut Problem::with_name("forward_problem")
                     .add_annotated_formulas(self.stable_premises.clone())
                     .add_annotated_formulas(self.forward_premises)
+                    .add_annotated_formulas(definitions)
                     .add_annotated_formulas(
                         self.proof_outline
                             .forward_lemmas
@@ -732,6 +935,11 @@ impl Task for AssembledExternalEquivalenceTask {
             self.direction,
             fol::Direction::Universal | fol::Direction::Backward
         ) {
+            let definitions = required_definitions(
+                &self.proof_outline.backward_definitions,
+                &self.backward_conclusions,
+            );
+
             let mut axioms = self.stable_premises.clone();
             axioms.extend(self.backward_premises.clone());
             axioms.extend(
@@ -758,6 +966,7 @@ impl Task for AssembledExternalEquivalenceTask {
                 &mut Problem::with_name("backward_problem")
                     .add_annotated_formulas(self.stable_premises)
                     .add_annotated_formulas(self.backward_premises)
+                    .add_annotated_formulas(definitions)
                     .add_annotated_formulas(
                         self.proof_outline
                             .backward_lemmas
@@ -774,3 +983,115 @@ impl Task for AssembledExternalEquivalenceTask {
         Ok(WithWarnings::flawless(problems))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{
+            ExternalEquivalenceTask, ExternalEquivalenceTaskError, ExternalEquivalenceTaskWarning,
+        },
+        crate::{
+            command_line::arguments::Decomposition,
+            syntax_tree::fol,
+            verifying::{problem::Problem, task::Task},
+        },
+        either::Either,
+        indexmap::IndexSet,
+    };
+
+    fn task(specification: &str, program: &str, proof_outline: &str) -> ExternalEquivalenceTask {
+        ExternalEquivalenceTask {
+            specification: Either::Left(specification.parse().unwrap()),
+            program: program.parse().unwrap(),
+            user_guide: "input: e/2. output: t/2.".parse().unwrap(),
+            proof_outline: proof_outline.parse().unwrap(),
+            decomposition: Decomposition::Independent,
+            direction: fol::Direction::Universal,
+            bypass_tightness: false,
+            natural: false,
+            simplify: true,
+            break_equivalences: true,
+        }
+    }
+
+    fn conjecture_predicates(problems: &[Problem], direction: &str) -> IndexSet<String> {
+        problems
+            .iter()
+            .filter(|problem| problem.name.starts_with(direction))
+            .flat_map(|problem| problem.conjectures())
+            .flat_map(|conjecture| conjecture.formula.predicates())
+            .map(|predicate| predicate.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_decompose_non_tight() {
+        // Both programs define the transitive closure, but recurse on different sides
+        let result = task(
+            "t(X, Y) :- e(X, Y). t(X, Z) :- e(X, Y), t(Y, Z).",
+            "t(X, Y) :- e(X, Y). t(X, Z) :- t(X, Y), e(Y, Z).",
+            "",
+        )
+        .decompose()
+        .unwrap();
+
+        let rankings: Vec<_> = result
+            .warnings
+            .iter()
+            .filter_map(|warning| match warning {
+                ExternalEquivalenceTaskWarning::RankingPredicates {
+                    rankings,
+                    direction,
+                    ..
+                } => Some((
+                    rankings.iter().map(|p| p.to_string()).collect::<Vec<_>>(),
+                    *direction,
+                )),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            rankings,
+            [
+                (vec!["rank_t/3".to_string()], fol::Direction::Backward),
+                (vec!["rank_t_p/3".to_string()], fol::Direction::Forward),
+            ]
+        );
+
+        // The loop formulas of each program are conjectures in the direction it is proven,
+        // and the clashing ranking predicate of the program is renamed
+        let forward = conjecture_predicates(&result.data, "forward");
+        assert!(forward.contains("rank_t_p/3"));
+        assert!(!forward.contains("rank_t/3"));
+        let backward = conjecture_predicates(&result.data, "backward");
+        assert!(backward.contains("rank_t/3"));
+        assert!(!backward.contains("rank_t_p/3"));
+    }
+
+    #[test]
+    fn test_decompose_ranking_definition() {
+        let specification = "t(X, Y) :- e(X, Y). t(X, Z) :- e(X, Y), t(Y, Z).";
+        let program = "t(X, Y) :- e(X, Y).";
+
+        // The loop formulas of the specification are proven in the backward direction
+        let result = task(
+            specification,
+            program,
+            "definition(backward): forall X Y N$i (rank_t(X, Y, N$i) <-> N$i = 0 and e(X, Y)).",
+        )
+        .decompose();
+        assert!(result.is_ok());
+
+        // ... and are axioms in the forward direction
+        let result = task(
+            specification,
+            program,
+            "definition(forward): forall X Y N$i (rank_t(X, Y, N$i) <-> N$i = 0 and e(X, Y)).",
+        )
+        .decompose();
+        assert!(matches!(
+            result,
+            Err(ExternalEquivalenceTaskError::InvalidRankingDefinition(_))
+        ));
+    }
+}
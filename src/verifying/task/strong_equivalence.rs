@@ -6,29 +6,36 @@ use {
             compose::Compose as _,
             with_warnings::{Result, WithWarnings},
         },
+        formatting::fol::fof,
         simplifying::fol::{classic::CLASSIC, ht::HT, intuitionistic::INTUITIONISTIC},
         syntax_tree::{asp, fol},
         translating::{
-            gamma::{self, gamma},
-            tau_star::tau_star,
+            gamma,
+            provenance::{trace_gamma, trace_natural, trace_tau_star},
         },
         verifying::{
             problem::{AnnotatedFormula, Problem, Role},
             task::Task,
         },
     },
+    indexmap::IndexSet,
     std::convert::Infallible,
     thiserror::Error,
 };
 
 #[derive(Error, Debug)]
-pub enum StrongEquivalenceTaskError {}
+pub enum StrongEquivalenceTaskError {
+    #[error("the following formula contains integers or comparisons other than equality, which intuitionistic provers do not support: {0} -- verify strong equivalence without `--intuitionistic`")]
+    UnsupportedByIntuitionisticProvers(fol::Formula),
+}
 
 pub struct StrongEquivalenceTask {
     pub left: asp::Program,
     pub right: asp::Program,
     pub decomposition: Decomposition,
     pub direction: fol::Direction,
+    pub natural: bool,
+    pub intuitionistic: bool,
     pub simplify: bool,
     pub break_equivalences: bool,
 }
@@ -58,6 +65,73 @@ impl StrongEquivalenceTask {
             formulas: predicates.into_iter().map(transition).collect(),
         }
     }
+
+    /// Valid formulas of quantified here-and-there that are added to intuitionistic logic
+    ///
+    /// This is sound but incomplete: Hosoi's axiom schema `F or (F -> G) or not G` is only instantiated for atoms `F` and `G`
+    /// whose predicates occur together in a rule, and the axiom `exists X (F(X) -> forall X F(X))` of quantified here-and-there is missing.
+    /// Besides, equality is decidable. There is at most one instance per ordered pair of predicates of each rule.
+    fn ht_axioms(&self) -> fol::Theory {
+        fn atom(p: &asp::Predicate, variable: &str) -> fol::Formula {
+            fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(fol::Atom {
+                predicate_symbol: p.symbol.clone(),
+                terms: (1..=p.arity)
+                    .map(|i| fol::GeneralTerm::Variable(format!("{variable}{i}")))
+                    .collect(),
+            }))
+        }
+
+        fn negation(formula: fol::Formula) -> fol::Formula {
+            fol::Formula::UnaryFormula {
+                connective: fol::UnaryConnective::Negation,
+                formula: formula.into(),
+            }
+        }
+
+        fn excluded_middle(formula: fol::Formula) -> fol::Formula {
+            fol::Formula::disjoin([formula.clone(), negation(formula)]).universal_closure()
+        }
+
+        // Pairs of predicates that never meet in a rule are left out, instead of instantiating the schema for all pairs
+        let mut pairs = IndexSet::new();
+        for rule in self.left.rules.iter().chain(&self.right.rules) {
+            let predicates = rule.predicates();
+            for p in &predicates {
+                for q in &predicates {
+                    pairs.insert((p.clone(), q.clone()));
+                }
+            }
+        }
+
+        let mut formulas = vec![excluded_middle(fol::Formula::AtomicFormula(
+            fol::AtomicFormula::Comparison(fol::Comparison {
+                term: fol::GeneralTerm::Variable("X".into()),
+                guards: vec![fol::Guard {
+                    relation: fol::Relation::Equal,
+                    term: fol::GeneralTerm::Variable("Y".into()),
+                }],
+            }),
+        ))];
+
+        for (p, q) in &pairs {
+            let f = atom(p, "X");
+            let g = atom(q, "Y");
+            formulas.push(
+                fol::Formula::disjoin([
+                    f.clone(),
+                    fol::Formula::BinaryFormula {
+                        connective: fol::BinaryConnective::Implication,
+                        lhs: f.into(),
+                        rhs: g.clone().into(),
+                    },
+                    negation(g),
+                ])
+                .universal_closure(),
+            );
+        }
+
+        fol::Theory { formulas }
+    }
 }
 
 impl Task for StrongEquivalenceTask {
@@ -65,41 +139,57 @@ impl Task for StrongEquivalenceTask {
     type Warning = Infallible;
 
     fn decompose(self) -> Result<Vec<Problem>, Self::Warning, Self::Error> {
-        let transition_axioms = self.transition_axioms(); // These are the "forall X (hp(X) -> tp(X))" axioms.
+        // Here-and-there is either encoded classically by gamma and the "forall X (hp(X) -> tp(X))" axioms,
+        // or axiomatized on top of intuitionistic logic for intuitionistic provers
+        let (axiom_name, axioms) = if self.intuitionistic {
+            ("ht_axiom", self.ht_axioms())
+        } else {
+            ("transition_axiom", self.transition_axioms())
+        };
 
-        let mut left = tau_star(self.left);
-        let mut right = tau_star(self.right);
+        let trace = if self.natural {
+            trace_natural
+        } else {
+            trace_tau_star
+        };
+        let mut left = trace(self.left);
+        let mut right = trace(self.right);
 
         if self.simplify {
             let mut portfolio = [INTUITIONISTIC, HT].concat().into_iter().compose();
-            left = left
-                .into_iter()
-                .map(|f| f.apply_fixpoint(&mut portfolio))
-                .collect();
-            right = right
-                .into_iter()
-                .map(|f| f.apply_fixpoint(&mut portfolio))
-                .collect();
+            left = left.map(|f| f.apply_fixpoint(&mut portfolio));
+            right = right.map(|f| f.apply_fixpoint(&mut portfolio));
         }
 
-        left = gamma(left);
-        right = gamma(right);
+        if !self.intuitionistic {
+            left = trace_gamma(left);
+            right = trace_gamma(right);
 
-        if self.simplify {
-            let mut portfolio = [INTUITIONISTIC, HT, CLASSIC].concat().into_iter().compose();
-            left = left
-                .into_iter()
-                .map(|f| f.apply_fixpoint(&mut portfolio))
-                .collect();
-            right = right
-                .into_iter()
-                .map(|f| f.apply_fixpoint(&mut portfolio))
-                .collect();
+            if self.simplify {
+                let mut portfolio = [INTUITIONISTIC, HT, CLASSIC].concat().into_iter().compose();
+                left = left.map(|f| f.apply_fixpoint(&mut portfolio));
+                right = right.map(|f| f.apply_fixpoint(&mut portfolio));
+            }
         }
 
         if self.break_equivalences {
-            left = crate::breaking::fol::ht::break_equivalences_theory(left);
-            right = crate::breaking::fol::ht::break_equivalences_theory(right);
+            left = left.flat_map(crate::breaking::fol::ht::break_equivalences_formula);
+            right = right.flat_map(crate::breaking::fol::ht::break_equivalences_formula);
+        }
+
+        // Intuitionistic provers only read untyped first-order logic
+        if self.intuitionistic {
+            if let Some(formula) = left
+                .theory
+                .formulas
+                .iter()
+                .chain(&right.theory.formulas)
+                .find(|formula| !fof::supports(formula))
+            {
+                return Err(
+                    StrongEquivalenceTaskError::UnsupportedByIntuitionisticProvers(formula.clone()),
+                );
+            }
         }
 
         let mut problems = Vec::new();
@@ -109,20 +199,23 @@ impl Task for StrongEquivalenceTask {
         ) {
             problems.push(
                 Problem::with_name("forward")
-                    .add_theory(transition_axioms.clone(), |i, formula| AnnotatedFormula {
-                        name: format!("transition_axiom_{i}"),
+                    .add_theory(axioms.clone(), |i, formula| AnnotatedFormula {
+                        name: format!("{axiom_name}_{i}"),
                         role: Role::Axiom,
                         formula,
+                        provenance: None,
                     })
-                    .add_theory(left.clone(), |i, formula| AnnotatedFormula {
+                    .add_traced_theory(left.clone(), |i, formula| AnnotatedFormula {
                         name: format!("left_{i}"),
                         role: Role::Axiom,
                         formula,
+                        provenance: None,
                     })
-                    .add_theory(right.clone(), |i, formula| AnnotatedFormula {
+                    .add_traced_theory(right.clone(), |i, formula| AnnotatedFormula {
                         name: format!("right_{i}"),
                         role: Role::Conjecture,
                         formula,
+                        provenance: None,
                     })
                     .rename_conflicting_symbols()
                     .create_unique_formula_names(),
@@ -134,20 +227,23 @@ impl Task for StrongEquivalenceTask {
         ) {
             problems.push(
                 Problem::with_name("backward")
-                    .add_theory(transition_axioms, |i, formula| AnnotatedFormula {
-                        name: format!("transition_axiom_{i}"),
+                    .add_theory(axioms, |i, formula| AnnotatedFormula {
+                        name: format!("{axiom_name}_{i}"),
                         role: Role::Axiom,
                         formula,
+                        provenance: None,
                     })
-                    .add_theory(right, |i, formula| AnnotatedFormula {
+                    .add_traced_theory(right, |i, formula| AnnotatedFormula {
                         name: format!("right_{i}"),
                         role: Role::Axiom,
                         formula,
+                        provenance: None,
                     })
-                    .add_theory(left, |i, formula| AnnotatedFormula {
+                    .add_traced_theory(left, |i, formula| AnnotatedFormula {
                         name: format!("left_{i}"),
                         role: Role::Conjecture,
                         formula,
+                        provenance: None,
                     })
                     .rename_conflicting_symbols()
                     .create_unique_formula_names(),
@@ -165,3 +261,74 @@ impl Task for StrongEquivalenceTask {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{StrongEquivalenceTask, StrongEquivalenceTaskError},
+        crate::{command_line::arguments::Decomposition, syntax_tree::fol, verifying::task::Task},
+    };
+
+    #[test]
+    fn test_ht_axioms() {
+        let task = StrongEquivalenceTask {
+            left: "p(X) :- q.".parse().unwrap(),
+            right: "q.".parse().unwrap(),
+            decomposition: Decomposition::Independent,
+            direction: fol::Direction::Universal,
+            natural: false,
+            intuitionistic: true,
+            simplify: true,
+            break_equivalences: true,
+        };
+
+        let axioms = task.ht_axioms();
+        assert_eq!(axioms.formulas.len(), 1 + 4);
+        assert_eq!(
+            axioms.formulas[0],
+            "forall X Y (X = Y or not X = Y)".parse().unwrap()
+        );
+        assert_eq!(
+            axioms.formulas[2],
+            "forall X1 (p(X1) or (p(X1) -> q) or not q)"
+                .parse()
+                .unwrap()
+        );
+
+        // Predicates of different rules are not paired
+        let task = StrongEquivalenceTask {
+            left: "p :- q. r :- s.".parse().unwrap(),
+            right: "p :- q. r :- s.".parse().unwrap(),
+            ..task
+        };
+        assert_eq!(task.ht_axioms().formulas.len(), 1 + 4 + 4);
+    }
+
+    #[test]
+    fn test_decompose_intuitionistic() {
+        let task = |left: &str, right: &str| StrongEquivalenceTask {
+            left: left.parse().unwrap(),
+            right: right.parse().unwrap(),
+            decomposition: Decomposition::Independent,
+            direction: fol::Direction::Universal,
+            natural: false,
+            intuitionistic: true,
+            simplify: true,
+            break_equivalences: true,
+        };
+
+        let problems = task("p(X) :- q(X), X != a.", "p(X) :- q(X), a != X.")
+            .decompose()
+            .unwrap()
+            .data;
+        assert_eq!(problems.len(), 2);
+        for problem in problems {
+            assert!(problem.to_fof().unwrap().contains("conjecture"));
+        }
+
+        assert!(matches!(
+            task("p(X) :- X = 1..3.", "p(1). p(2). p(3).").decompose(),
+            Err(StrongEquivalenceTaskError::UnsupportedByIntuitionisticProvers(_))
+        ));
+    }
+}